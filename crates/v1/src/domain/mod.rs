@@ -1,2 +1,3 @@
 pub mod entities;
+pub mod service;
 pub mod value_obj;
@@ -0,0 +1,106 @@
+//! JWT（HS256）のアクセストークンを発行・検証するモジュール。
+
+use crate::{
+    domain::value_obj::public_id::PublicId,
+    error::{AppError, AppResult},
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// 起動時にConfigから設定される署名用の鍵。
+static KEYS: OnceCell<(EncodingKey, DecodingKey)> = OnceCell::new();
+
+/// アクセストークンのクレーム。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claims {
+    /// ユーザーの公開ID。
+    pub sub: String,
+    /// 発行日時（UNIX timestamp）。
+    pub iat: i64,
+    /// 有効期限（UNIX timestamp）。
+    pub exp: i64,
+}
+
+/// 署名に使用するシークレットを設定する（起動時に一度だけ有効）。
+pub fn configure(secret: &str) {
+    let keys = (
+        EncodingKey::from_secret(secret.as_bytes()),
+        DecodingKey::from_secret(secret.as_bytes()),
+    );
+    if KEYS.set(keys).is_err() {
+        warn!("JWT secret is already configured, ignoring new value");
+    }
+}
+
+/// ユーザーの公開IDを`sub`とするアクセストークンを発行する。
+pub fn issue(user: &PublicId, ttl: Duration) -> AppResult<String> {
+    let (encoding_key, _) = keys()?;
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: user.to_string(),
+        iat: now,
+        exp: now + ttl.num_seconds(),
+    };
+    encode(&Header::new(Algorithm::HS256), &claims, encoding_key).map_err(|e| {
+        AppError::InternalServerError(Some(format!("Failed to issue access token: {}", e)))
+    })
+}
+
+/// アクセストークンを検証し，クレームを返す。
+/// 期限切れ・署名不正などはすべて`AppError::Unauthorized`とする。
+pub fn verify(token: &str) -> AppResult<Claims> {
+    let (_, decoding_key) = keys()?;
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.leeway = 0;
+    validation.set_required_spec_claims(&["sub", "iat", "exp"]);
+    decode::<Claims>(token, decoding_key, &validation)
+        .map(|data| data.claims)
+        .map_err(|_| AppError::Unauthorized(Some("アクセストークンが無効です。".into())))
+}
+
+fn keys() -> AppResult<&'static (EncodingKey, DecodingKey)> {
+    KEYS.get()
+        .ok_or_else(|| AppError::InternalServerError(Some("JWT secret is not configured".into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() {
+        configure("test-secret");
+    }
+
+    /// 発行したトークンを検証できることを確認
+    #[test]
+    fn issue_then_verify() {
+        setup();
+        let user = PublicId::generate();
+        let token = issue(&user, Duration::minutes(5)).unwrap();
+        let claims = verify(&token).unwrap();
+        assert_eq!(claims.sub, user.to_string());
+        assert_eq!(claims.exp - claims.iat, 300);
+    }
+
+    /// 期限切れのトークンは401になることを確認
+    #[test]
+    fn expired_token_is_unauthorized() {
+        setup();
+        let token = issue(&PublicId::generate(), Duration::seconds(-1)).unwrap();
+        assert!(matches!(verify(&token), Err(AppError::Unauthorized(_))));
+    }
+
+    /// 署名を改ざんしたトークンは401になることを確認
+    #[test]
+    fn tampered_signature_is_unauthorized() {
+        setup();
+        let token = issue(&PublicId::generate(), Duration::minutes(5)).unwrap();
+        let (payload, signature) = token.rsplit_once('.').unwrap();
+        let flipped = if signature.starts_with('A') { 'B' } else { 'A' };
+        let tampered = format!("{}.{}{}", payload, flipped, &signature[1..]);
+        assert!(matches!(verify(&tampered), Err(AppError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod jwt;
+pub mod password_hasher;
+pub mod randomart;
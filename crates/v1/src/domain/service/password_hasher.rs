@@ -0,0 +1,116 @@
+//! Argon2によるパスワードのハッシュ化・検証を行うモジュール。
+
+use crate::error::HashingError;
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::{Error as Argon2Error, SaltString, rand_core::OsRng},
+};
+use once_cell::sync::OnceCell;
+use tracing::warn;
+
+/// 起動時にConfigから設定されるArgon2のコストパラメータ。
+static PARAMS: OnceCell<Params> = OnceCell::new();
+
+/// ハッシュ化に使用するコストパラメータを設定する（起動時に一度だけ有効）。
+pub fn configure(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<(), HashingError> {
+    let params = Params::new(memory_kib, iterations, parallelism, None)
+        .map_err(|e| HashingError::Argon2(e.into()))?;
+    if PARAMS.set(params).is_err() {
+        warn!("Argon2 parameters are already configured, ignoring new values");
+    }
+    Ok(())
+}
+
+/// 平文パスワードをArgon2idでハッシュ化し，PHC文字列を返す。
+pub fn hash_password(plain: &str) -> Result<String, HashingError> {
+    let params = PARAMS.get().cloned().unwrap_or_default();
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password(plain.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// 平文パスワードとPHC文字列のハッシュを照合する。
+/// 一致しない場合は`HashingError::PasswordMismatch`を返す。
+pub fn verify_password(plain: &str, hash: &str) -> Result<(), HashingError> {
+    // コストパラメータはハッシュ文字列に含まれるものが使用される。
+    let parsed = PasswordHash::new(hash)?;
+    Argon2::default()
+        .verify_password(plain.as_bytes(), &parsed)
+        .map_err(|e| match e {
+            Argon2Error::Password => HashingError::PasswordMismatch,
+            other => HashingError::Argon2(other),
+        })
+}
+
+/// Argon2の計算を含む`f`をブロッキング用のスレッドで実行し，非同期ランタイムのワーカーを占有しないようにする。
+pub async fn spawn_blocking<T, F>(f: F) -> Result<T, HashingError>
+where
+    F: FnOnce() -> Result<T, HashingError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
+}
+
+/// `hash_password`をブロッキング用のスレッドで実行する。
+pub async fn hash_password_async(plain: String) -> Result<String, HashingError> {
+    spawn_blocking(move || hash_password(&plain)).await
+}
+
+/// `verify_password`をブロッキング用のスレッドで実行する。
+pub async fn verify_password_async(plain: String, hash: String) -> Result<(), HashingError> {
+    spawn_blocking(move || verify_password(&plain, &hash)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ブロッキング用のスレッドでハッシュ化・検証でき，パニックはエラーとして返ることを確認
+    #[tokio::test]
+    async fn async_helpers_run_on_blocking_threads() {
+        let hash = hash_password_async("Correct-Horse-42".into())
+            .await
+            .unwrap();
+        assert!(
+            verify_password_async("Correct-Horse-42".into(), hash.clone())
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            verify_password_async("Wrong-Horse-42".into(), hash).await,
+            Err(HashingError::PasswordMismatch)
+        ));
+        assert!(matches!(
+            spawn_blocking::<(), _>(|| panic!("boom")).await,
+            Err(HashingError::Task(_))
+        ));
+    }
+
+    /// ハッシュ化したパスワードが検証に成功することを確認
+    #[test]
+    fn hash_then_verify() {
+        let hash = hash_password("Correct-Horse-42").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("Correct-Horse-42", &hash).is_ok());
+    }
+
+    /// 誤ったパスワードは`PasswordMismatch`になることを確認
+    #[test]
+    fn wrong_password_is_mismatch() {
+        let hash = hash_password("Correct-Horse-42").unwrap();
+        assert!(matches!(
+            verify_password("Wrong-Horse-42", &hash),
+            Err(HashingError::PasswordMismatch)
+        ));
+    }
+
+    /// 不正なハッシュ文字列はArgon2のエラーになることを確認
+    #[test]
+    fn malformed_hash_is_argon2_error() {
+        assert!(matches!(
+            verify_password("Correct-Horse-42", "not-a-hash"),
+            Err(HashingError::Argon2(_))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+//! OpenSSHのrandomart（drunken bishopアルゴリズム）を生成するモジュール。
+
+/// 盤面の幅。
+const WIDTH: usize = 17;
+/// 盤面の高さ。
+const HEIGHT: usize = 9;
+/// 訪問回数に応じて描画する文字（末尾2文字は開始・終了位置用）。
+const SYMBOLS: &[u8] = b" .o+=*BOX@%&#/^SE";
+
+/// フィンガープリントから17x9の枠付きrandomartを生成する。
+/// 同一の入力に対しては常に同一の出力となる。
+pub fn randomart(fingerprint: &[u8], header: &str) -> String {
+    let mut field = [[0usize; WIDTH]; HEIGHT];
+    let (mut x, mut y) = (WIDTH / 2, HEIGHT / 2);
+    let start = (x, y);
+    let max_count = SYMBOLS.len() - 3;
+
+    // 各バイトを下位ビットから2ビットずつ読み，斜めに移動する（壁で止まる）。
+    for byte in fingerprint {
+        let mut input = *byte;
+        for _ in 0..4 {
+            x = if input & 0x1 != 0 {
+                (x + 1).min(WIDTH - 1)
+            } else {
+                x.saturating_sub(1)
+            };
+            y = if input & 0x2 != 0 {
+                (y + 1).min(HEIGHT - 1)
+            } else {
+                y.saturating_sub(1)
+            };
+            field[y][x] = (field[y][x] + 1).min(max_count);
+            input >>= 2;
+        }
+    }
+
+    let mut art = String::with_capacity((WIDTH + 3) * (HEIGHT + 2));
+    art.push_str(&border(header));
+    art.push('\n');
+    for (row_y, row) in field.iter().enumerate() {
+        art.push('|');
+        for (col_x, count) in row.iter().enumerate() {
+            let symbol = if (col_x, row_y) == (x, y) {
+                SYMBOLS[SYMBOLS.len() - 1]
+            } else if (col_x, row_y) == start {
+                SYMBOLS[SYMBOLS.len() - 2]
+            } else {
+                SYMBOLS[*count]
+            };
+            art.push(symbol as char);
+        }
+        art.push_str("|\n");
+    }
+    art.push_str(&border(""));
+    art
+}
+
+/// `+---[header]---+`形式の枠線を生成する（空の場合は見出し無し）。
+fn border(header: &str) -> String {
+    let label = if header.is_empty() {
+        String::new()
+    } else {
+        let inner: String = header.chars().take(WIDTH - 2).collect();
+        format!("[{}]", inner)
+    };
+    let label_len = label.chars().count();
+    let left = (WIDTH - label_len) / 2;
+    let right = WIDTH - label_len - left;
+    format!("+{}{}{}+", "-".repeat(left), label, "-".repeat(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HEIGHT, WIDTH, randomart};
+
+    /// 固定のフィンガープリントに対して出力が安定していることを確認
+    #[test]
+    fn stable_output_for_fixed_fingerprint() {
+        // 0x00は4回とも左上へ移動する。
+        let art = randomart(&[0x00], "TEST");
+        let expected = [
+            "+-----[TEST]------+",
+            "|    E            |",
+            "|     .           |",
+            "|      .          |",
+            "|       .         |",
+            "|        S        |",
+            "|                 |",
+            "|                 |",
+            "|                 |",
+            "|                 |",
+            "+-----------------+",
+        ]
+        .join("\n");
+        assert_eq!(art, expected);
+        assert_eq!(art, randomart(&[0x00], "TEST"));
+    }
+
+    /// 枠を含めて19x11の大きさであることを確認
+    #[test]
+    fn box_dimensions() {
+        let art = randomart(
+            &[0xde, 0xad, 0xbe, 0xef, 0x01, 0x23, 0x45, 0x67],
+            "A VERY LONG HEADER",
+        );
+        let lines: Vec<&str> = art.lines().collect();
+        assert_eq!(lines.len(), HEIGHT + 2);
+        assert!(lines.iter().all(|l| l.chars().count() == WIDTH + 2));
+    }
+}
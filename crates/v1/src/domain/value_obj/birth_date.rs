@@ -0,0 +1,350 @@
+//! `YYYYmmdd`，`YYYY-mm-dd`，`YYYY/mm/dd`形式の生年月日のVO
+
+use crate::domain::value_obj::normalized_str::NormalizedString;
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use once_cell::sync::OnceCell;
+use tracing::warn;
+
+/// 起動時にConfigから設定される，「本日」を判定するタイムゾーン。
+static TIMEZONE: OnceCell<Tz> = OnceCell::new();
+
+/// 「本日」を判定するタイムゾーンを設定する（起動時に一度だけ有効）。
+pub fn set_timezone(tz: Tz) {
+    if TIMEZONE.set(tz).is_err() {
+        warn!("Timezone is already set, ignoring {}", tz);
+    }
+}
+
+/// 「本日」を判定するタイムゾーンを返す（未設定の場合はUTC）。
+pub fn timezone() -> Tz {
+    TIMEZONE.get().copied().unwrap_or(Tz::UTC)
+}
+
+/// 集計用の年齢区分（正確な年齢を公開しないために使用する）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgeBracket {
+    /// 18歳未満
+    Under18,
+    /// 18〜29歳
+    From18To29,
+    /// 30〜49歳
+    From30To49,
+    /// 50〜64歳
+    From50To64,
+    /// 65歳以上
+    Over65,
+}
+
+impl AgeBracket {
+    /// 年齢から区分を返す。
+    pub fn from_age(age: u32) -> Self {
+        match age {
+            0..=17 => Self::Under18,
+            18..=29 => Self::From18To29,
+            30..=49 => Self::From30To49,
+            50..=64 => Self::From50To64,
+            _ => Self::Over65,
+        }
+    }
+}
+
+/// 生年月日（未来日は不可）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BirthDate(NaiveDate);
+
+impl BirthDate {
+    /// 最小長（`YYYYmmdd`）。
+    const MIN_LEN: usize = 8;
+    /// 最大長（`YYYY-mm-dd`）。
+    const MAX_LEN: usize = 10;
+    /// 受け付ける入力フォーマット（先頭から順に試行する）。
+    const FORMATS: [&'static str; 3] = ["%Y%m%d", "%Y-%m-%d", "%Y/%m/%d"];
+
+    /// 本日の日付を基準に生年月日を検証する。
+    /// `required`がfalseかつ空文字の場合は None を返す。
+    pub fn new<S: AsRef<str>>(input: S, required: bool) -> AppResult<Option<Self>> {
+        Self::new_at(input, required, Self::today())
+    }
+
+    /// 指定した日付を「本日」として生年月日を検証する。
+    pub fn new_at<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        today: NaiveDate,
+    ) -> AppResult<Option<Self>> {
+        Self::new_with_age_bounds_at(input, required, None, None, today)
+    }
+
+    /// 本日の日付を基準に生年月日を検証し，年齢が`min_age`〜`max_age`の範囲内であることを確認する。
+    pub fn new_with_age_bounds<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        min_age: Option<u32>,
+        max_age: Option<u32>,
+    ) -> AppResult<Option<Self>> {
+        Self::new_with_age_bounds_at(input, required, min_age, max_age, Self::today())
+    }
+
+    /// 指定した日付を「本日」として，年齢の範囲チェックを含めて生年月日を検証する。
+    pub fn new_with_age_bounds_at<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        min_age: Option<u32>,
+        max_age: Option<u32>,
+        today: NaiveDate,
+    ) -> AppResult<Option<Self>> {
+        let Some(normalized) =
+            NormalizedString::new(input, required, Some(Self::MIN_LEN), Some(Self::MAX_LEN))?
+        else {
+            return Ok(None);
+        };
+
+        let date = Self::FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(normalized.as_str(), format).ok())
+            .ok_or_else(|| {
+                AppError::UnprocessableContent(Some(
+                    "生年月日はYYYYMMDD，YYYY-MM-DD，YYYY/MM/DDのいずれかの形式で入力してください。"
+                        .into(),
+                ))
+            })?;
+        if date > today {
+            return Err(AppError::UnprocessableContent(Some(
+                "生年月日に未来の日付は指定できません。".into(),
+            )));
+        }
+
+        let birth_date = Self(date);
+        let age = birth_date.calculate_to_age_at(today)?;
+        if let Some(min) = min_age
+            && age < min
+        {
+            return Err(AppError::UnprocessableContent(Some(format!(
+                "{}歳未満の方はご利用いただけません。",
+                min
+            ))));
+        }
+        if let Some(max) = max_age
+            && age > max
+        {
+            return Err(AppError::UnprocessableContent(Some(format!(
+                "生年月日が正しくありません（{}歳を超えています）。",
+                max
+            ))));
+        }
+
+        Ok(Some(birth_date))
+    }
+
+    /// 生年月日を返す。
+    pub fn as_date(&self) -> &NaiveDate {
+        &self.0
+    }
+
+    /// 本日時点の年齢を返す。
+    pub fn calculate_to_age(&self) -> AppResult<u32> {
+        self.calculate_to_age_at(Self::today())
+    }
+
+    /// 指定した日付時点の年齢を返す。
+    /// 2/29生まれの場合，うるう年以外は2/28を誕生日として扱う。
+    pub fn calculate_to_age_at(&self, today: NaiveDate) -> AppResult<u32> {
+        let birth = self.0;
+        if today < birth {
+            return Err(AppError::InternalServerError(Some(format!(
+                "Reference date {} is before the birth date {}",
+                today, birth
+            ))));
+        }
+
+        let birthday = if birth.month() == 2 && birth.day() == 29 && !is_leap_year(today.year()) {
+            (2, 28)
+        } else {
+            (birth.month(), birth.day())
+        };
+
+        let mut age = today.year() - birth.year();
+        if (today.month(), today.day()) < birthday {
+            age -= 1;
+        }
+        Ok(age as u32)
+    }
+
+    /// 本日時点の年齢区分を返す。
+    pub fn age_bracket(&self) -> AppResult<AgeBracket> {
+        self.age_bracket_at(Self::today())
+    }
+
+    /// 指定した日付時点の年齢区分を返す。
+    pub fn age_bracket_at(&self, today: NaiveDate) -> AppResult<AgeBracket> {
+        self.calculate_to_age_at(today).map(AgeBracket::from_age)
+    }
+
+    /// `now`を`tz`で見た場合の日付を返す。
+    /// 年齢の判定を時刻・タイムゾーンに依存せず検証するために使用する。
+    pub fn today_in(tz: Tz, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&tz).date_naive()
+    }
+
+    /// 設定されたタイムゾーンにおける本日の日付を返す。
+    fn today() -> NaiveDate {
+        Self::today_in(timezone(), Utc::now())
+    }
+}
+
+/// 必須の生年月日として検証する。
+impl TryFrom<&str> for BirthDate {
+    type Error = AppError;
+
+    fn try_from(input: &str) -> AppResult<Self> {
+        Self::new(input, true)?
+            .ok_or_else(|| AppError::UnprocessableContent(Some("値を入力してください。".into())))
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AgeBracket, BirthDate};
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn birth_date(input: &str) -> BirthDate {
+        BirthDate::new_at(input, true, date(2025, 6, 1))
+            .unwrap()
+            .unwrap()
+    }
+
+    /// 正しい形式が受理され，未来日や不正な形式がエラーになることを確認
+    #[test]
+    fn parses_and_rejects_future_dates() {
+        assert_eq!(birth_date("20000101").as_date(), &date(2000, 1, 1));
+        assert!(BirthDate::new_at("20250602", true, date(2025, 6, 1)).is_err());
+        assert!(BirthDate::new_at("20231340", true, date(2025, 6, 1)).is_err());
+        assert!(
+            BirthDate::new_at("", false, date(2025, 6, 1))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// 各フォーマットが受理され，存在しない日付はエラーになることを確認
+    #[test]
+    fn accepts_multiple_formats() {
+        assert_eq!(birth_date("20000105").as_date(), &date(2000, 1, 5));
+        assert_eq!(birth_date("2000-01-05").as_date(), &date(2000, 1, 5));
+        assert_eq!(birth_date("2000/01/05").as_date(), &date(2000, 1, 5));
+        assert_eq!(
+            birth_date("２０００－０１－０５").as_date(),
+            &date(2000, 1, 5)
+        );
+        assert!(BirthDate::new_at("2023-13-40", true, date(2025, 6, 1)).is_err());
+        assert!(BirthDate::new_at("2000.01.05", true, date(2025, 6, 1)).is_err());
+    }
+
+    /// 2/29生まれは，うるう年以外は2/28に年齢が加算されることを確認
+    #[test]
+    fn leap_day_birthday() {
+        let birth = birth_date("20000229");
+        assert_eq!(birth.calculate_to_age_at(date(2023, 2, 27)).unwrap(), 22);
+        assert_eq!(birth.calculate_to_age_at(date(2023, 2, 28)).unwrap(), 23);
+        assert_eq!(birth.calculate_to_age_at(date(2024, 2, 28)).unwrap(), 23);
+        assert_eq!(birth.calculate_to_age_at(date(2024, 2, 29)).unwrap(), 24);
+    }
+
+    /// 今年の誕生日を迎えていない場合は1歳少ないことを確認
+    #[test]
+    fn not_yet_had_birthday() {
+        let birth = birth_date("19901231");
+        assert_eq!(birth.calculate_to_age_at(date(2024, 12, 30)).unwrap(), 33);
+        assert_eq!(birth.calculate_to_age_at(date(2025, 1, 1)).unwrap(), 34);
+    }
+
+    /// 本日ちょうど最小年齢に達した場合は受理され，前日までは拒否されることを確認
+    #[test]
+    fn min_age_boundary() {
+        let today = date(2025, 6, 1);
+        assert!(BirthDate::new_with_age_bounds_at("20070601", true, Some(18), None, today).is_ok());
+        assert!(
+            BirthDate::new_with_age_bounds_at("20070602", true, Some(18), None, today).is_err()
+        );
+    }
+
+    /// 最大年齢を超える生年月日が拒否されることを確認
+    #[test]
+    fn max_age_boundary() {
+        let today = date(2025, 6, 1);
+        assert!(
+            BirthDate::new_with_age_bounds_at("18750601", true, None, Some(120), today).is_err()
+        );
+        assert!(BirthDate::new_with_age_bounds_at("18750601", true, None, None, today).is_ok());
+        assert!(
+            BirthDate::new_with_age_bounds_at("19050602", true, None, Some(120), today).is_ok()
+        );
+    }
+
+    /// 区分の境界となる誕生日で正しく分類されることを確認
+    #[test]
+    fn age_bracket_boundaries() {
+        let today = date(2025, 6, 1);
+        let cases = [
+            ("20070602", AgeBracket::Under18),    // 17歳
+            ("20070601", AgeBracket::From18To29), // 18歳
+            ("19950602", AgeBracket::From18To29), // 29歳
+            ("19950601", AgeBracket::From30To49), // 30歳
+            ("19750602", AgeBracket::From30To49), // 49歳
+            ("19750601", AgeBracket::From50To64), // 50歳
+            ("19600602", AgeBracket::From50To64), // 64歳
+            ("19600601", AgeBracket::Over65),     // 65歳
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                birth_date(input).age_bracket_at(today).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    /// TryFrom<&str>で必須の生年月日として変換できることを確認
+    #[test]
+    fn try_from_str() {
+        let birth = BirthDate::try_from("2000/01/05").unwrap();
+        assert_eq!(*birth.as_date(), date(2000, 1, 5));
+        assert!(BirthDate::try_from("").is_err());
+        assert!(BirthDate::try_from("2000-13-01").is_err());
+    }
+
+    /// 同じ時刻でもタイムゾーンによって日付が変わり，年齢の判定が異なることを確認
+    #[test]
+    fn today_depends_on_timezone() {
+        // 2025-05-31 20:00 UTC = 2025-06-01 05:00 JST
+        let now = Utc.with_ymd_and_hms(2025, 5, 31, 20, 0, 0).unwrap();
+        let utc = BirthDate::today_in(Tz::UTC, now);
+        let tokyo = BirthDate::today_in(Tz::Asia__Tokyo, now);
+        assert_eq!(utc, date(2025, 5, 31));
+        assert_eq!(tokyo, date(2025, 6, 1));
+
+        // 18歳の誕生日を迎えるのは東京のみ。
+        assert!(BirthDate::new_with_age_bounds_at("20070601", true, Some(18), None, tokyo).is_ok());
+        assert!(BirthDate::new_with_age_bounds_at("20070601", true, Some(18), None, utc).is_err());
+    }
+
+    /// 誕生日当日に年齢が加算されることを確認
+    #[test]
+    fn birthday_is_today() {
+        let birth = birth_date("19900601");
+        assert_eq!(birth.calculate_to_age_at(date(2025, 6, 1)).unwrap(), 35);
+        assert_eq!(birth.calculate_to_age_at(date(2025, 5, 31)).unwrap(), 34);
+    }
+}
@@ -0,0 +1,146 @@
+//! 簡易的なRFC準拠チェックを行うメールアドレスのVO
+
+use crate::domain::value_obj::normalized_str::NormalizedString;
+use crate::error::{AppError, AppResult};
+use std::fmt;
+
+/// メールアドレス（小文字化済み）。
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Email(String);
+
+impl Email {
+    /// メールアドレス全体の最大長。
+    const MAX_LEN: usize = 254;
+    /// ローカル部の最大長。
+    const MAX_LOCAL_LEN: usize = 64;
+
+    /// 入力をNFKC正規化・trim・小文字化した上で，メールアドレスとして検証する。
+    /// ローカル部も大文字小文字を区別せず，同じアドレスとして扱う。
+    /// `required`がfalseかつ空文字の場合は None を返す。
+    pub fn new<S: AsRef<str>>(input: S, required: bool) -> AppResult<Option<Self>> {
+        let Some(normalized) = NormalizedString::new(input, required, None, Some(Self::MAX_LEN))?
+        else {
+            return Ok(None);
+        };
+
+        let mut parts = normalized.as_str().split('@');
+        let (local, domain) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(local), Some(domain), None) => (local, domain),
+            _ => return Err(Self::invalid()),
+        };
+
+        if local.is_empty() || local.len() > Self::MAX_LOCAL_LEN || !Self::is_dot_atom(local) {
+            return Err(Self::invalid());
+        }
+        if domain.is_empty() || !domain.contains('.') || !Self::is_dot_atom(domain) {
+            return Err(Self::invalid());
+        }
+
+        Ok(Some(Self(format!(
+            "{}@{}",
+            local.to_lowercase(),
+            domain.to_lowercase()
+        ))))
+    }
+
+    /// メールアドレスを文字列として返す。
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// ログ出力用に，ローカル部の先頭1文字以外を伏せた値（例: `j***@example.com`）を返す。
+    /// ローカル部の長さが推測されないよう，伏せ字は常に3文字とする。
+    pub fn masked(&self) -> String {
+        Self::mask(&self.0)
+    }
+
+    /// 保存済みのメールアドレス文字列を`masked`と同じ形式で伏せる。
+    pub fn mask(address: &str) -> String {
+        let (local, domain) = address.split_once('@').unwrap_or((address, ""));
+        let first = local.chars().next().map(String::from).unwrap_or_default();
+        format!("{}***@{}", first, domain)
+    }
+
+    /// ドットで区切られた各要素が空でなく，空白を含まないことを確認する。
+    fn is_dot_atom(s: &str) -> bool {
+        s.split('.')
+            .all(|atom| !atom.is_empty() && !atom.chars().any(char::is_whitespace))
+    }
+
+    fn invalid() -> AppError {
+        AppError::UnprocessableContent(Some("メールアドレスの形式が正しくありません。".into()))
+    }
+}
+
+/// ログに個人情報が出力されないよう，マスクした値を表示する。
+impl fmt::Debug for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Email").field(&self.masked()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Email;
+
+    fn masked(input: &str) -> String {
+        Email::new(input, true).unwrap().unwrap().masked()
+    }
+
+    /// ローカル部の長さに関わらず先頭1文字のみが残り，ドメイン部はそのまま表示されることを確認
+    #[test]
+    fn masks_local_part() {
+        assert_eq!(masked("john.doe@example.com"), "j***@example.com");
+        assert_eq!(masked("jo@example.com"), "j***@example.com");
+        assert_eq!(masked("j@example.com"), "j***@example.com");
+        assert_eq!(masked("たろう@example.jp"), "た***@example.jp");
+    }
+
+    /// Debug出力がマスクされることを確認
+    #[test]
+    fn debug_is_masked() {
+        let email = Email::new("john.doe@example.com", true).unwrap().unwrap();
+        assert_eq!(format!("{:?}", email), r#"Email("j***@example.com")"#);
+    }
+
+    /// 正しい形式のメールアドレスが受理され，全体が小文字化されることを確認
+    #[test]
+    fn valid_addresses() {
+        let email = Email::new(" John.Doe@Example.COM ", true).unwrap().unwrap();
+        assert_eq!(email.as_str(), "john.doe@example.com");
+        assert!(Email::new("a+tag@sub.example.jp", true).unwrap().is_some());
+    }
+
+    /// 大文字小文字のみが異なるアドレスは同じ値になることを確認
+    #[test]
+    fn case_variants_are_equal() {
+        let upper = Email::new("User@X.com", true).unwrap().unwrap();
+        let lower = Email::new("user@x.com", true).unwrap().unwrap();
+        assert_eq!(upper, lower);
+    }
+
+    /// @が無い，または複数ある場合はエラーになることを確認
+    #[test]
+    fn rejects_missing_or_multiple_at() {
+        assert!(Email::new("example.com", true).is_err());
+        assert!(Email::new("a@b@example.com", true).is_err());
+        assert!(Email::new("@example.com", true).is_err());
+        assert!(Email::new("user@", true).is_err());
+    }
+
+    /// 先頭・末尾のドットはエラーになることを確認
+    #[test]
+    fn rejects_leading_and_trailing_dots() {
+        assert!(Email::new(".user@example.com", true).is_err());
+        assert!(Email::new("user.@example.com", true).is_err());
+        assert!(Email::new("user@.example.com", true).is_err());
+        assert!(Email::new("user@example.com.", true).is_err());
+    }
+
+    /// 任意項目で空文字の場合は None になることを確認
+    #[test]
+    fn optional_empty_is_none() {
+        assert!(Email::new("  ", false).unwrap().is_none());
+        assert!(Email::new("  ", true).is_err());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod birth_date;
+pub mod email;
 pub mod normalized_str;
+pub mod password;
+pub mod phone_number;
+pub mod public_id;
+pub mod session_id;
 pub mod user_id;
+pub mod user_name;
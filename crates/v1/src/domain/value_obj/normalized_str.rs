@@ -1,4 +1,369 @@
-//! 空文字禁止，NFKC正規化，最大長チェックを行う汎用VO
+//! 空文字禁止，Unicode正規化（デフォルトはNFKC），最大長チェックを行う汎用VO
 
 use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
 use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 適用するUnicode正規化形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalizationForm {
+    /// 互換分解・正規合成。全角英数字や`㈱`などを標準的な形に揃える。
+    #[default]
+    Nfkc,
+    /// 正規分解・正規合成。結合文字は合成するが，ユーザーが選んだ字形は保持する。
+    Nfc,
+    /// 正規化しない（前後の空白除去のみ）。
+    None,
+}
+
+/// 正規化後も残り，見た目では判別できない文字かを判定する。
+/// C0/C1制御文字，ゼロ幅文字，双方向テキストの制御文字が該当する。
+/// 絵文字の結合に使われるZWJ（U+200D）や，一部の言語で必要なZWNJ（U+200C）は許可する。
+fn is_invisible(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{200B}' // ZERO WIDTH SPACE
+            | '\u{2060}' // WORD JOINER
+            | '\u{FEFF}' // ZERO WIDTH NO-BREAK SPACE (BOM)
+            | '\u{061C}' // ARABIC LETTER MARK
+            | '\u{200E}'..='\u{200F}' // LRM, RLM
+            | '\u{202A}'..='\u{202E}' // LRE, RLE, PDF, LRO, RLO
+            | '\u{2066}'..='\u{2069}' // LRI, RLI, FSI, PDI
+        )
+}
+
+impl NormalizationForm {
+    fn apply(self, input: &str) -> String {
+        match self {
+            Self::Nfkc => input.nfkc().collect(),
+            Self::Nfc => input.nfc().collect(),
+            Self::None => input.to_owned(),
+        }
+    }
+}
+
+/// Unicode正規化（デフォルトはNFKC）及び前後の空白除去を行った文字列。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NormalizedString(String);
+
+impl NormalizedString {
+    /// 正規化前に許容する1書記素あたりのバイト数。
+    /// `max_len`の指定がある場合，`max_len * MAX_BYTES_PER_GRAPHEME`バイトを超える入力は
+    /// 正規化せずに長すぎるものとして扱い，巨大な入力の処理コストを抑える。
+    pub const MAX_BYTES_PER_GRAPHEME: usize = 8;
+
+    /// 入力をNFKC正規化・trimした上で，必須チェック及び長さ（書記素数）チェックを行う。
+    /// `required`がfalseかつ正規化後に空文字となる場合は None を返す。
+    pub fn new<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        min_len: Option<usize>,
+        max_len: Option<usize>,
+    ) -> AppResult<Option<Self>> {
+        Self::new_with_charset(input, required, min_len, max_len, None)
+    }
+
+    /// `new`と同様の検証を，NFKCの代わりに`form`で正規化した上で行う。
+    /// 表示名など，ユーザーが入力した字形を保持したい項目に使用する。
+    pub fn new_with_form<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        min_len: Option<usize>,
+        max_len: Option<usize>,
+        form: NormalizationForm,
+    ) -> AppResult<Option<Self>> {
+        Self::build(input.as_ref(), required, min_len, max_len, None, form)
+    }
+
+    /// `new`の検証に加え，各書記素の基底文字が`allowed`を満たすことを確認する。
+    /// `allowed`が None の場合は文字種を制限しない。
+    pub fn new_with_charset<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        min_len: Option<usize>,
+        max_len: Option<usize>,
+        allowed: Option<fn(char) -> bool>,
+    ) -> AppResult<Option<Self>> {
+        Self::build(
+            input.as_ref(),
+            required,
+            min_len,
+            max_len,
+            allowed,
+            NormalizationForm::Nfkc,
+        )
+    }
+
+    fn build(
+        input: &str,
+        required: bool,
+        min_len: Option<usize>,
+        max_len: Option<usize>,
+        allowed: Option<fn(char) -> bool>,
+        form: NormalizationForm,
+    ) -> AppResult<Option<Self>> {
+        // 正規化は入力長に比例するため，明らかに長すぎる入力は先に拒否する。
+        // 有効な長さの入力については，正規化後の書記素数で判定する。
+        if let Some(max) = max_len
+            && input.len() > max.saturating_mul(Self::MAX_BYTES_PER_GRAPHEME)
+        {
+            return Err(Self::too_long(max));
+        }
+
+        let normalized = form.apply(input);
+        let trimmed = normalized.trim();
+
+        // 空文字の場合は，必須ならエラー，任意なら None。
+        if trimmed.is_empty() {
+            return if required {
+                Err(AppError::UnprocessableContent(Some(
+                    "値を入力してください。".into(),
+                )))
+            } else {
+                Ok(None)
+            };
+        }
+
+        if let Some(invisible) = trimmed.chars().find(|&c| is_invisible(c)) {
+            return Err(AppError::UnprocessableContent(Some(format!(
+                "制御文字やゼロ幅文字，双方向制御文字は使用できません（U+{:04X}）。",
+                invisible as u32
+            ))));
+        }
+
+        let len = trimmed.graphemes(true).count();
+        if let Some(min) = min_len
+            && len < min
+        {
+            return Err(AppError::UnprocessableContent(Some(format!(
+                "{}文字以上で入力してください。",
+                min
+            ))));
+        }
+        if let Some(max) = max_len
+            && len > max
+        {
+            return Err(Self::too_long(max));
+        }
+
+        if let Some(allowed) = allowed
+            && let Some(invalid) = trimmed
+                .graphemes(true)
+                .find(|g| g.chars().next().is_some_and(|c| !allowed(c)))
+        {
+            return Err(AppError::UnprocessableContent(Some(format!(
+                "使用できない文字が含まれています: '{}'",
+                invalid
+            ))));
+        }
+
+        Ok(Some(Self(trimmed.to_owned())))
+    }
+
+    fn too_long(max: usize) -> AppError {
+        AppError::UnprocessableContent(Some(format!("{}文字以内で入力してください。", max)))
+    }
+
+    /// 正規化済みの文字列を返す。
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 正規化済みの文字列を取り出す。
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Serialize for NormalizedString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// `new`（必須，長さ制限無し）で正規化・検証した上でデシリアライズする。
+/// 長さを制限する場合は`UserName`など型付けされたVOを使用すること。
+impl<'de> Deserialize<'de> for NormalizedString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match Self::new(&s, true, None, None) {
+            Ok(Some(normalized)) => Ok(normalized),
+            Ok(None) => Err(D::Error::custom("値を入力してください。")),
+            Err(e) => Err(D::Error::custom(
+                e.detail().cloned().unwrap_or_else(|| e.to_string()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NormalizationForm, NormalizedString};
+
+    /// 全角英数字がNFKC正規化され，前後の空白が除去されることを確認
+    #[test]
+    fn normalizes_and_trims() {
+        let s = NormalizedString::new("  ＡＢＣ１２３　", true, None, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(s.as_str(), "ABC123");
+    }
+
+    /// NFCでは`㈱`や全角英字が保持され，NFKCでは展開されることを確認
+    #[test]
+    fn nfc_preserves_compatibility_characters() {
+        let with_form = |form| {
+            NormalizedString::new_with_form("㈱Ａ", true, None, None, form)
+                .unwrap()
+                .unwrap()
+                .into_inner()
+        };
+        assert_eq!(with_form(NormalizationForm::Nfkc), "(株)A");
+        assert_eq!(with_form(NormalizationForm::Nfc), "㈱Ａ");
+        assert_eq!(with_form(NormalizationForm::None), "㈱Ａ");
+        // NFCでも結合文字は合成される。
+        assert_eq!(
+            NormalizedString::new_with_form("か\u{3099}", true, None, None, NormalizationForm::Nfc)
+                .unwrap()
+                .unwrap()
+                .as_str(),
+            "が"
+        );
+    }
+
+    /// 全ての正規化形式で前後の空白が除去されることを確認
+    #[test]
+    fn trims_in_every_form() {
+        for form in [
+            NormalizationForm::Nfkc,
+            NormalizationForm::Nfc,
+            NormalizationForm::None,
+        ] {
+            let s = NormalizedString::new_with_form(" \t alice \n", true, None, None, form)
+                .unwrap()
+                .unwrap();
+            assert_eq!(s.as_str(), "alice", "{:?}", form);
+        }
+    }
+
+    /// ゼロ幅スペースや制御文字を含む入力は，その文字を示してエラーになることを確認
+    #[test]
+    fn rejects_zero_width_and_control_characters() {
+        let err = NormalizedString::new("ad\u{200B}min\u{200B}", true, None, None).unwrap_err();
+        assert!(err.detail().unwrap().contains("U+200B"));
+        let err = NormalizedString::new("\u{FEFF}admin", true, None, None).unwrap_err();
+        assert!(err.detail().unwrap().contains("U+FEFF"));
+        let err = NormalizedString::new("ali\u{0007}ce", true, None, None).unwrap_err();
+        assert!(err.detail().unwrap().contains("U+0007"));
+        let err = NormalizedString::new("ali\u{0085}ce", true, None, None).unwrap_err();
+        assert!(err.detail().unwrap().contains("U+0085"));
+    }
+
+    /// 右から左への上書き（RLO）による偽装はエラーになることを確認
+    #[test]
+    fn rejects_bidi_override() {
+        // 表示上は"invoice_fdp.exe"ではなく"invoice_exe.pdf"に見える。
+        let err = NormalizedString::new("invoice_\u{202E}fdp.exe", true, None, None).unwrap_err();
+        assert!(err.detail().unwrap().contains("U+202E"));
+        assert!(NormalizedString::new("a\u{2066}b", true, None, None).is_err());
+    }
+
+    /// 絵文字の結合に使われるZWJは許可されることを確認
+    #[test]
+    fn allows_zero_width_joiner_in_emoji() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let s = NormalizedString::new(family, true, Some(1), Some(3))
+            .unwrap()
+            .unwrap();
+        assert_eq!(s.as_str(), family);
+    }
+
+    /// JSON文字列としてシリアライズされ，デシリアライズ時に正規化されることを確認
+    #[test]
+    fn serde_round_trip() {
+        let s = NormalizedString::new("Alice", true, None, None)
+            .unwrap()
+            .unwrap();
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(json, r#""Alice""#);
+        assert_eq!(serde_json::from_str::<NormalizedString>(&json).unwrap(), s);
+
+        let s: NormalizedString = serde_json::from_str(r#"" ＡＢＣ ""#).unwrap();
+        assert_eq!(s.as_str(), "ABC");
+    }
+
+    /// 空文字や不正な文字はデシリアライズ時にエラーになることを確認
+    #[test]
+    fn deserialize_rejects_invalid_input() {
+        let err = serde_json::from_str::<NormalizedString>(r#""  ""#).unwrap_err();
+        assert!(err.to_string().contains("値を入力してください"), "{}", err);
+        assert!(serde_json::from_str::<NormalizedString>(r#""a\u200bb""#).is_err());
+        assert!(serde_json::from_str::<NormalizedString>("42").is_err());
+    }
+
+    /// 空文字は必須ならエラー，任意なら None になることを確認
+    #[test]
+    fn empty_input() {
+        assert!(NormalizedString::new("　 ", true, None, None).is_err());
+        assert!(
+            NormalizedString::new("　 ", false, None, None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// 長さは書記素数で判定されることを確認
+    #[test]
+    fn length_is_counted_in_graphemes() {
+        // "が"（結合文字）と絵文字はそれぞれ1文字として数える。
+        assert!(NormalizedString::new("か\u{3099}👍", true, Some(2), Some(2)).is_ok());
+        assert!(NormalizedString::new("abc", true, Some(4), None).is_err());
+        assert!(NormalizedString::new("abc", true, None, Some(2)).is_err());
+    }
+
+    /// 巨大な入力は正規化の前に長さ超過として拒否されることを確認
+    #[test]
+    fn huge_input_is_rejected_before_normalization() {
+        let huge = "Ａ".repeat(4 * 1024 * 1024);
+        let err = NormalizedString::new(&huge, true, None, Some(64)).unwrap_err();
+        assert_eq!(err.detail().unwrap(), "64文字以内で入力してください。");
+
+        // 空白のみの入力は正規化・trimの後なら None になるため，長さ超過になるのはバイト数の判定が先に行われた場合のみ。
+        let blank = " ".repeat(64 * NormalizedString::MAX_BYTES_PER_GRAPHEME + 1);
+        let err = NormalizedString::new(&blank, false, None, Some(64)).unwrap_err();
+        assert_eq!(err.detail().unwrap(), "64文字以内で入力してください。");
+        let blank = " ".repeat(64 * NormalizedString::MAX_BYTES_PER_GRAPHEME);
+        assert!(
+            NormalizedString::new(&blank, false, None, Some(64))
+                .unwrap()
+                .is_none()
+        );
+
+        // 上限付近の入力は書記素数で判定される（全角文字は3バイトだが1文字）。
+        let fullwidth = "Ａ".repeat(64);
+        assert!(NormalizedString::new(&fullwidth, true, None, Some(64)).is_ok());
+        let combining = "か\u{3099}".repeat(64);
+        assert!(NormalizedString::new(&combining, true, None, Some(64)).is_ok());
+    }
+
+    /// 許可された文字種のみの入力が受理されることを確認
+    #[test]
+    fn charset_accepts_allowed_characters() {
+        let allowed: Option<fn(char) -> bool> = Some(|c| c.is_ascii_alphanumeric());
+        let s = NormalizedString::new_with_charset("Ａｂｃ123", true, None, None, allowed)
+            .unwrap()
+            .unwrap();
+        assert_eq!(s.as_str(), "Abc123");
+    }
+
+    /// 許可されていない文字種を含む入力は，その文字を示してエラーになることを確認
+    #[test]
+    fn charset_rejects_mixed_script() {
+        let allowed: Option<fn(char) -> bool> = Some(|c| c.is_ascii_alphanumeric());
+        let err =
+            NormalizedString::new_with_charset("abcあ123", true, None, None, allowed).unwrap_err();
+        assert!(err.detail().unwrap().contains('あ'));
+    }
+}
@@ -0,0 +1,146 @@
+//! 強度ポリシーを満たすパスワードのVO（平文はDrop時にゼロ化する）
+
+use crate::error::{AppError, AppResult};
+use std::fmt;
+use unicode_segmentation::UnicodeSegmentation;
+use zeroize::Zeroizing;
+
+/// 強度ポリシーを満たした平文パスワード。
+/// 意味のある文字を失わないよう，NFKC正規化は行わない。
+pub struct Password(Zeroizing<String>);
+
+impl Password {
+    /// デフォルトの最小長（書記素数）。
+    pub const DEFAULT_MIN_LEN: usize = 12;
+    /// 最大長（書記素数）。ハッシュ計算の負荷を抑えるための上限。
+    pub const MAX_LEN: usize = 128;
+    /// 満たす必要のある文字種（小文字，大文字，数字，記号）の数。
+    const REQUIRED_CLASSES: usize = 3;
+
+    /// デフォルトの最小長でパスワードを検証する。
+    /// `user_name`が指定された場合，それと同一のパスワードを拒否する。
+    pub fn new<S: Into<String>>(input: S, user_name: Option<&str>) -> AppResult<Self> {
+        Self::with_min_len(input, Self::DEFAULT_MIN_LEN, user_name)
+    }
+
+    /// 最小長を指定してパスワードを検証する。
+    pub fn with_min_len<S: Into<String>>(
+        input: S,
+        min_len: usize,
+        user_name: Option<&str>,
+    ) -> AppResult<Self> {
+        let input = Zeroizing::new(input.into());
+
+        if input.is_empty() {
+            return Err(Self::invalid("パスワードを入力してください。"));
+        }
+        if input.trim() != input.as_str() {
+            return Err(Self::invalid(
+                "パスワードの先頭・末尾に空白は使用できません。",
+            ));
+        }
+
+        let len = input.graphemes(true).count();
+        if len < min_len {
+            return Err(Self::invalid(&format!(
+                "パスワードは{}文字以上で入力してください。",
+                min_len
+            )));
+        }
+        if len > Self::MAX_LEN {
+            return Err(Self::invalid(&format!(
+                "パスワードは{}文字以内で入力してください。",
+                Self::MAX_LEN
+            )));
+        }
+
+        if Self::count_classes(&input) < Self::REQUIRED_CLASSES {
+            return Err(Self::invalid(
+                "パスワードには小文字・大文字・数字・記号のうち3種類以上を含めてください。",
+            ));
+        }
+
+        if let Some(user_name) = user_name
+            && input.to_lowercase() == user_name.to_lowercase()
+        {
+            return Err(Self::invalid(
+                "ユーザー名と同じパスワードは使用できません。",
+            ));
+        }
+
+        Ok(Self(input))
+    }
+
+    /// 平文パスワードを返す。
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 含まれる文字種（小文字，大文字，数字，記号）の数を返す。
+    fn count_classes(s: &str) -> usize {
+        let (mut lower, mut upper, mut digit, mut symbol) = (false, false, false, false);
+        for c in s.chars() {
+            if c.is_lowercase() {
+                lower = true;
+            } else if c.is_uppercase() {
+                upper = true;
+            } else if c.is_ascii_digit() {
+                digit = true;
+            } else if !c.is_alphanumeric() && !c.is_whitespace() {
+                symbol = true;
+            }
+        }
+        [lower, upper, digit, symbol].iter().filter(|&&b| b).count()
+    }
+
+    fn invalid(message: &str) -> AppError {
+        AppError::UnprocessableContent(Some(message.into()))
+    }
+}
+
+/// 平文がログに出力されないよう，Debugでは値を伏せる。
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password([REDACTED])")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Password;
+
+    /// ポリシーを満たすパスワードが受理されることを確認
+    #[test]
+    fn accepts_strong_password() {
+        let password = Password::new("Correct-Horse-42", Some("alice")).unwrap();
+        assert_eq!(password.as_str(), "Correct-Horse-42");
+        assert_eq!(format!("{:?}", password), "Password([REDACTED])");
+    }
+
+    /// 最小長に満たない場合はエラーになることを確認
+    #[test]
+    fn rejects_short_password() {
+        assert!(Password::new("Ab1!Ab1!Ab1", None).is_err());
+        assert!(Password::with_min_len("Ab1!Ab1!Ab1", 8, None).is_ok());
+    }
+
+    /// 文字種が3種類未満の場合はエラーになることを確認
+    #[test]
+    fn rejects_insufficient_character_classes() {
+        assert!(Password::new("alllowercase1234", None).is_err());
+        assert!(Password::new("NoDigitsOrSymbols", None).is_err());
+    }
+
+    /// 先頭・末尾の空白はエラーになることを確認
+    #[test]
+    fn rejects_surrounding_whitespace() {
+        assert!(Password::new(" Correct-Horse-42", None).is_err());
+        assert!(Password::new("Correct-Horse-42 ", None).is_err());
+    }
+
+    /// ユーザー名と同一のパスワードはエラーになることを確認
+    #[test]
+    fn rejects_password_equal_to_user_name() {
+        assert!(Password::new("Alice_Wonder_99", Some("alice_wonder_99")).is_err());
+    }
+}
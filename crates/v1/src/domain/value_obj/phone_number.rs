@@ -0,0 +1,173 @@
+//! E.164形式に正規化する電話番号のVO
+
+use crate::domain::value_obj::normalized_str::NormalizedString;
+use crate::error::{AppError, AppResult};
+use std::fmt;
+
+/// E.164形式（`+`から始まる7〜15桁の数字）の電話番号。
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// E.164で許容される最小桁数。
+    const MIN_DIGITS: usize = 7;
+    /// E.164で許容される最大桁数。
+    const MAX_DIGITS: usize = 15;
+    /// 正規化前の入力として許容する最大長（区切り文字を含む）。
+    const MAX_INPUT_LEN: usize = 32;
+    /// マスク時に表示する先頭・末尾の桁数。
+    const VISIBLE_DIGITS: usize = 4;
+    /// マスク時に最低限伏せる桁数。
+    const MIN_MASKED_DIGITS: usize = 3;
+
+    /// 入力をNFKC正規化し，区切り文字（空白，ハイフン，括弧）を除去した上でE.164形式に変換する。
+    /// 先頭が`0`の国内形式の場合は`default_country_code`（例: "81"）を付与する。
+    /// `required`がfalseかつ空文字の場合は None を返す。
+    pub fn new<S: AsRef<str>>(
+        input: S,
+        required: bool,
+        default_country_code: &str,
+    ) -> AppResult<Option<Self>> {
+        let Some(normalized) =
+            NormalizedString::new(input, required, None, Some(Self::MAX_INPUT_LEN))?
+        else {
+            return Ok(None);
+        };
+
+        let stripped: String = normalized
+            .as_str()
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '(' | ')'))
+            .collect();
+
+        let digits = match stripped.strip_prefix('+') {
+            Some(rest) => rest.to_owned(),
+            None => match stripped.strip_prefix('0') {
+                // 国内形式は先頭の0を国番号に置き換える。
+                Some(rest) => format!("{}{}", default_country_code, rest),
+                None => stripped,
+            },
+        };
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(AppError::UnprocessableContent(Some(
+                "電話番号には数字のみを入力してください。".into(),
+            )));
+        }
+        if !(Self::MIN_DIGITS..=Self::MAX_DIGITS).contains(&digits.len()) {
+            return Err(AppError::UnprocessableContent(Some(format!(
+                "電話番号は{}〜{}桁で入力してください。",
+                Self::MIN_DIGITS,
+                Self::MAX_DIGITS
+            ))));
+        }
+
+        Ok(Some(Self(format!("+{}", digits))))
+    }
+
+    /// E.164形式の電話番号を返す。
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// ログ出力用に，先頭・末尾の4桁以外を伏せた値（例: `+8190****5678`）を返す。
+    /// 桁数が少ない場合は先頭を減らし，最低3桁は伏せる。
+    pub fn masked(&self) -> String {
+        Self::mask(&self.0)
+    }
+
+    /// 検証前の電話番号文字列を`masked`と同じ規則で伏せる（区切り文字も1文字として数える）。
+    pub fn mask(number: &str) -> String {
+        let (sign, rest) = match number.strip_prefix('+') {
+            Some(rest) => ("+", rest),
+            None => ("", number),
+        };
+        let chars: Vec<char> = rest.chars().collect();
+        let suffix = Self::VISIBLE_DIGITS.min(chars.len().saturating_sub(Self::MIN_MASKED_DIGITS));
+        let prefix =
+            Self::VISIBLE_DIGITS.min(chars.len().saturating_sub(suffix + Self::MIN_MASKED_DIGITS));
+        format!(
+            "{}{}{}{}",
+            sign,
+            chars[..prefix].iter().collect::<String>(),
+            "*".repeat(chars.len() - prefix - suffix),
+            chars[chars.len() - suffix..].iter().collect::<String>()
+        )
+    }
+}
+
+/// ログに個人情報が出力されないよう，マスクした値を表示する。
+impl fmt::Debug for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PhoneNumber").field(&self.masked()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PhoneNumber;
+
+    /// 先頭・末尾の4桁以外が伏せられ，桁数が少ない場合も最低3桁は伏せられることを確認
+    #[test]
+    fn masks_middle_digits() {
+        let masked = |input| {
+            PhoneNumber::new(input, true, "81")
+                .unwrap()
+                .unwrap()
+                .masked()
+        };
+        assert_eq!(masked("090-1234-5678"), "+8190****5678");
+        assert_eq!(masked("+1 202 555 0143"), "+1202***0143");
+        assert_eq!(masked("+1234567"), "+***4567");
+    }
+
+    /// 検証前の文字列も区切り文字を含めて伏せられ，非ASCII文字でもパニックしないことを確認
+    #[test]
+    fn masks_raw_input() {
+        assert_eq!(PhoneNumber::mask("090-1234-5678"), "090-*****5678");
+        assert_eq!(
+            PhoneNumber::mask("０９０１２３４５６７８"),
+            "０９０１***５６７８"
+        );
+        assert_eq!(PhoneNumber::mask("12"), "**");
+        assert_eq!(PhoneNumber::mask("12345"), "***45");
+    }
+
+    /// 日本の国内形式（全角・ハイフン区切り）がE.164形式に変換されることを確認
+    #[test]
+    fn converts_japanese_domestic_format() {
+        let phone = PhoneNumber::new("０９０－１２３４－５６７８", true, "81")
+            .unwrap()
+            .unwrap();
+        assert_eq!(phone.as_str(), "+819012345678");
+
+        let phone = PhoneNumber::new("(03) 1234-5678", true, "81")
+            .unwrap()
+            .unwrap();
+        assert_eq!(phone.as_str(), "+81312345678");
+    }
+
+    /// 国番号付きの入力はそのまま受理されることを確認
+    #[test]
+    fn keeps_international_format() {
+        let phone = PhoneNumber::new("+1 415 555 2671", true, "81")
+            .unwrap()
+            .unwrap();
+        assert_eq!(phone.as_str(), "+14155552671");
+    }
+
+    /// 数字以外の文字や桁数の過不足はエラーになることを確認
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(PhoneNumber::new("090-abcd-5678", true, "81").is_err());
+        assert!(PhoneNumber::new("+81+9012345678", true, "81").is_err());
+        assert!(PhoneNumber::new("0123", true, "81").is_err());
+        assert!(PhoneNumber::new("+1234567890123456", true, "81").is_err());
+    }
+
+    /// 任意項目で空文字の場合は None になることを確認
+    #[test]
+    fn optional_empty_is_none() {
+        assert!(PhoneNumber::new("", false, "81").unwrap().is_none());
+    }
+}
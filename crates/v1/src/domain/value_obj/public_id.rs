@@ -0,0 +1,96 @@
+//! 外部公開用のユーザー識別子（UUID v7）のVO
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use uuid::Uuid;
+
+/// 外部公開用の識別子。時刻順にソート可能なUUID v7を使用する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PublicId(Uuid);
+
+impl PublicId {
+    /// 新しいPublicIdを生成する。
+    pub fn generate() -> Self {
+        Self(Uuid::now_v7())
+    }
+
+    /// 文字列からPublicIdを生成する。
+    pub fn parse(input: &str) -> AppResult<Self> {
+        Uuid::parse_str(input.trim())
+            .map(Self)
+            .map_err(|_| AppError::BadRequest(Some("公開IDの形式が正しくありません。".into())))
+    }
+
+    /// 内部のUUIDを返す。
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+/// DBから読み込んだUUIDをPublicIdとして扱う。
+impl From<Uuid> for PublicId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl TryFrom<&str> for PublicId {
+    type Error = AppError;
+
+    fn try_from(input: &str) -> AppResult<Self> {
+        Self::parse(input)
+    }
+}
+
+impl fmt::Display for PublicId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.hyphenated().fmt(f)
+    }
+}
+
+impl Serialize for PublicId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublicId;
+
+    /// 生成したIDが文字列経由で往復できることを確認
+    #[test]
+    fn round_trip_parse() {
+        let id = PublicId::generate();
+        assert_eq!(id.as_uuid().get_version_num(), 7);
+        assert_eq!(PublicId::parse(&id.to_string()).unwrap(), id);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", id));
+        assert_eq!(serde_json::from_str::<PublicId>(&json).unwrap(), id);
+    }
+
+    /// UUIDでない文字列はエラーになることを確認
+    #[test]
+    fn rejects_non_uuid() {
+        assert!(PublicId::parse("not-a-uuid").is_err());
+        assert!(PublicId::parse("").is_err());
+        assert!(serde_json::from_str::<PublicId>("\"12345\"").is_err());
+    }
+
+    /// TryFrom<&str>がparseと同じ結果になることを確認
+    #[test]
+    fn try_from_str() {
+        let id = PublicId::generate();
+        assert_eq!(PublicId::try_from(id.to_string().as_str()).unwrap(), id);
+        assert!(PublicId::try_from("not-a-uuid").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! 暗号論的乱数による256bitのセッショントークンのVO
+
+use crate::error::{AppError, AppResult};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::{RngCore, rngs::OsRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// セッション識別子（256bitの乱数をパディング無しのbase64urlで表現したもの）。
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SessionId(String);
+
+impl SessionId {
+    /// トークンのバイト数（256bit）。
+    const BYTES: usize = 32;
+    /// base64url（パディング無し）でエンコードした際の長さ。
+    const ENCODED_LEN: usize = 43;
+
+    /// OSの暗号論的乱数生成器で新しいSessionIdを生成する。
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; Self::BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        Self(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// 文字列からSessionIdを生成する（エンコードと長さを検証する）。
+    pub fn parse(input: &str) -> AppResult<Self> {
+        let input = input.trim();
+        let valid = input.len() == Self::ENCODED_LEN
+            && URL_SAFE_NO_PAD
+                .decode(input)
+                .is_ok_and(|bytes| bytes.len() == Self::BYTES);
+        if !valid {
+            return Err(AppError::Unauthorized(Some(
+                "セッションIDが正しくありません。".into(),
+            )));
+        }
+        Ok(Self(input.to_owned()))
+    }
+
+    /// トークンを文字列として返す。
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// トークンがログに出力されないよう，Debugでは先頭のみ表示する。
+impl fmt::Debug for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SessionId({}…)", &self.0[..4])
+    }
+}
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for SessionId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SessionId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SessionId;
+
+    /// 生成したトークンが43文字で，毎回異なることを確認
+    #[test]
+    fn generates_256_bit_tokens() {
+        let id = SessionId::generate();
+        assert_eq!(id.as_str().len(), 43);
+        assert_ne!(id, SessionId::generate());
+    }
+
+    /// 生成したトークンが文字列・JSON経由で往復できることを確認
+    #[test]
+    fn round_trip_parse() {
+        let id = SessionId::generate();
+        assert_eq!(SessionId::parse(id.as_str()).unwrap(), id);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(serde_json::from_str::<SessionId>(&json).unwrap(), id);
+    }
+
+    /// 不正なトークンは拒否されることを確認
+    #[test]
+    fn rejects_malformed_tokens() {
+        let id = SessionId::generate();
+        assert!(SessionId::parse("").is_err());
+        assert!(SessionId::parse(&id.as_str()[..42]).is_err());
+        assert!(SessionId::parse(&format!("{}=", &id.as_str()[..42])).is_err());
+        assert!(SessionId::parse(&"!".repeat(43)).is_err());
+        assert!(SessionId::parse("6f1c2d4e-0000-4000-8000-000000000000").is_err());
+    }
+}
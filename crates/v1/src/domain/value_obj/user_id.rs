@@ -1 +1,97 @@
+//! 内部用のユーザー識別子（usersテーブルの主キー）のVO
 
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// 内部用のユーザー識別子（正の整数）。外部には公開しない。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UserId(i64);
+
+impl UserId {
+    /// 正の整数であることを検証してUserIdを生成する。
+    pub fn new(value: i64) -> AppResult<Self> {
+        if value <= 0 {
+            return Err(Self::invalid());
+        }
+        Ok(Self(value))
+    }
+
+    fn invalid() -> AppError {
+        AppError::BadRequest(Some("ユーザーIDは正の整数である必要があります。".into()))
+    }
+
+    /// 内部の整数値を返す。
+    pub fn as_i64(&self) -> i64 {
+        self.0
+    }
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// パスパラメータ等の文字列からUserIdを生成する。
+impl FromStr for UserId {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> AppResult<Self> {
+        let value = s.trim().parse::<i64>().map_err(|_| Self::invalid())?;
+        Self::new(value)
+    }
+}
+
+impl Serialize for UserId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for UserId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = i64::deserialize(deserializer)?;
+        Self::new(value)
+            .map_err(|e| serde::de::Error::custom(e.detail().cloned().unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserId;
+
+    /// 正の整数のみ受理されることを確認
+    #[test]
+    fn accepts_only_positive_values() {
+        assert_eq!(UserId::new(1).unwrap().as_i64(), 1);
+        assert!(UserId::new(0).is_err());
+        assert!(UserId::new(-1).is_err());
+    }
+
+    /// 文字列から変換でき，数値以外や0以下はエラーになることを確認
+    #[test]
+    fn from_str_parses_positive_integers() {
+        assert_eq!("42".parse::<UserId>().unwrap().as_i64(), 42);
+        assert!("abc".parse::<UserId>().is_err());
+        assert!("".parse::<UserId>().is_err());
+        assert!("0".parse::<UserId>().is_err());
+        assert!("-1".parse::<UserId>().is_err());
+    }
+
+    /// 内部の整数値がそのまま表示されることを確認
+    #[test]
+    fn display_prints_inner_value() {
+        assert_eq!(UserId::new(42).unwrap().to_string(), "42");
+    }
+
+    /// serdeで往復でき，0以下はデシリアライズ時に拒否されることを確認
+    #[test]
+    fn serde_enforces_positivity() {
+        let id = UserId::new(7).unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), "7");
+        assert_eq!(serde_json::from_str::<UserId>("7").unwrap(), id);
+        assert!(serde_json::from_str::<UserId>("0").is_err());
+        assert!(serde_json::from_str::<UserId>("-3").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! 使用可能な文字種を`[a-z0-9_]`に制限したユーザー名のVO
+
+use crate::domain::value_obj::normalized_str::NormalizedString;
+use crate::error::{AppError, AppResult};
+
+/// ユーザー名（小文字化済み）。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UserName(String);
+
+impl UserName {
+    /// 最小長。
+    const MIN_LEN: usize = 3;
+    /// 最大長。
+    const MAX_LEN: usize = 32;
+    /// デフォルトで使用を禁止する予約語。
+    pub const DEFAULT_RESERVED: &'static [&'static str] = &["admin", "root"];
+
+    /// 入力をNFKC正規化・小文字化した上で，ユーザー名として検証する。
+    /// `reserved`に含まれる名前は使用できない。
+    pub fn new<S: AsRef<str>>(input: S, reserved: &[&str]) -> AppResult<Self> {
+        let normalized =
+            NormalizedString::new(input, true, Some(Self::MIN_LEN), Some(Self::MAX_LEN))?
+                .ok_or_else(|| Self::invalid("ユーザー名を入力してください。"))?;
+        let name = normalized.as_str().to_lowercase();
+
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(Self::invalid(
+                "ユーザー名には半角英数字とアンダースコアのみ使用できます。",
+            ));
+        }
+        if name.starts_with('_') {
+            return Err(Self::invalid(
+                "ユーザー名の先頭にアンダースコアは使用できません。",
+            ));
+        }
+        if reserved.iter().any(|r| r.eq_ignore_ascii_case(&name)) {
+            return Err(Self::invalid("このユーザー名は使用できません。"));
+        }
+
+        Ok(Self(name))
+    }
+
+    /// ユーザー名を文字列として返す。
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn invalid(message: &str) -> AppError {
+        AppError::UnprocessableContent(Some(message.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserName;
+
+    /// 正しいユーザー名が受理されることを確認
+    #[test]
+    fn accepts_valid_names() {
+        let name = UserName::new("alice_01", UserName::DEFAULT_RESERVED).unwrap();
+        assert_eq!(name.as_str(), "alice_01");
+    }
+
+    /// 大文字・全角文字が正規化されることを確認
+    #[test]
+    fn normalizes_uppercase() {
+        let name = UserName::new("Ａｌｉｃｅ", UserName::DEFAULT_RESERVED).unwrap();
+        assert_eq!(name.as_str(), "alice");
+    }
+
+    /// 許可されていない記号や先頭のアンダースコア，長さ違反はエラーになることを確認
+    #[test]
+    fn rejects_illegal_names() {
+        assert!(UserName::new("alice-01", &[]).is_err());
+        assert!(UserName::new("alice.b", &[]).is_err());
+        assert!(UserName::new("ありす", &[]).is_err());
+        assert!(UserName::new("_alice", &[]).is_err());
+        assert!(UserName::new("ab", &[]).is_err());
+        assert!(UserName::new("a".repeat(33), &[]).is_err());
+    }
+
+    /// 予約語はエラーになり，予約語リストは差し替え可能であることを確認
+    #[test]
+    fn rejects_reserved_names() {
+        assert!(UserName::new("Admin", UserName::DEFAULT_RESERVED).is_err());
+        assert!(UserName::new("root", UserName::DEFAULT_RESERVED).is_err());
+        assert!(UserName::new("root", &[]).is_ok());
+        assert!(UserName::new("support", &["support"]).is_err());
+    }
+}
@@ -1,27 +1,65 @@
 //! アプリケーション全体で使用するエラー型及び変換ロジックを集約するモジュール。
 
-use crate::presentation::dto::common_dto::ApiError;
+use crate::presentation::{
+    dto::common_dto::{ApiError, FieldError},
+    middleware::request_id::current_request_id,
+};
 use AppError::*;
 use argon2::password_hash::Error as Argon2Error;
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use chrono::Utc;
+use once_cell::sync::OnceCell;
 use sqlx::Error as SqlxError;
+use std::io::ErrorKind;
 use thiserror::Error;
 use tracing::*;
 
 /// プロジェクト全体で使用するResult型。
 pub type AppResult<T> = Result<T, AppError>;
 
+/// 503レスポンスに付与する<Retry-After>のデフォルト秒数。
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 5;
+
+/// 起動時にConfigから設定される<Retry-After>の秒数。
+static RETRY_AFTER_SECS: OnceCell<u64> = OnceCell::new();
+
+/// 503レスポンスに付与する<Retry-After>の秒数を設定する（起動時に一度だけ有効）。
+pub fn set_retry_after_secs(secs: u64) {
+    if RETRY_AFTER_SECS.set(secs).is_err() {
+        warn!("Retry-After seconds is already set, ignoring {}", secs);
+    }
+}
+
+/// 503レスポンスに付与する<Retry-After>の秒数を返す（未設定ならデフォルト値）。
+pub fn retry_after_secs() -> u64 {
+    *RETRY_AFTER_SECS.get().unwrap_or(&DEFAULT_RETRY_AFTER_SECS)
+}
+
+/// レスポンスに<Retry-After>を付与する。
+/// <Detail>に秒数以外のメッセージを含めた429等，秒数を別に指定したい場合に使用する。
+pub fn with_retry_after(mut response: Response, secs: u64) -> Response {
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, secs.into());
+    response
+}
+
+/// RFC 7807 Problem Detailsのメディアタイプ。
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
 /// PostgreSQLのSQLSTATEコード定数。
 pub mod sqlx_error_code {
     pub const UNIQUE_VIOLATION: &str = "23505";
     pub const FK_VIOLATION: &str = "23503";
     pub const NOT_NULL_VIOLATION: &str = "23502";
     pub const CHECK_VIOLATION: &str = "23514";
+    pub const QUERY_CANCELED: &str = "57014";
+    pub const SERIALIZATION_FAILURE: &str = "40001";
+    pub const DEADLOCK_DETECTED: &str = "40P01";
 }
 
 /// アプリケーション全体で使用される上位エラー型。
@@ -40,13 +78,26 @@ pub enum AppError {
     RequestTimeout(Option<String>),
     #[error("Conflict")]
     Conflict(Option<String>),
+    #[error("Payload Too Large")]
+    PayloadTooLarge(Option<String>),
     #[error("I'm a Teapot")]
     ImATeapot(Option<String>),
+    /// rate limit error（<Detail>が秒数の場合は<Retry-After>として返す）
+    #[error("Too Many Requests")]
+    TooManyRequests(Option<String>),
     /// validation error
     #[error("Unprocessable Content")]
     UnprocessableContent(Option<String>),
+    /// validation error（フィールド毎のエラーを<errors>として返す）
+    #[error("Unprocessable Content")]
+    UnprocessableContentFields(Vec<FieldError>),
+    /// 楽観的ロックのバージョンが指定されていない
+    #[error("Precondition Required")]
+    PreconditionRequired(Option<String>),
     #[error("Internal Server Error")]
     InternalServerError(Option<String>),
+    #[error("Service Unavailable")]
+    ServiceUnavailable(Option<String>),
 }
 
 impl AppError {
@@ -60,9 +111,35 @@ impl AppError {
             NotFound(_) => StatusCode::NOT_FOUND,
             RequestTimeout(_) => StatusCode::REQUEST_TIMEOUT,
             Conflict(_) => StatusCode::CONFLICT,
+            PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             ImATeapot(_) => StatusCode::IM_A_TEAPOT,
-            UnprocessableContent(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            UnprocessableContent(_) | UnprocessableContentFields(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
             InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+    /// AppErrorをRFC 7807の<type>に使用する安定したURIに変換する。
+    pub fn type_uri(&self) -> &'static str {
+        match self {
+            BadRequest(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/400",
+            Unauthorized(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/401",
+            Forbidden(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/403",
+            NotFound(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/404",
+            RequestTimeout(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/408",
+            Conflict(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/409",
+            PayloadTooLarge(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/413",
+            ImATeapot(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/418",
+            TooManyRequests(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/429",
+            UnprocessableContent(_) | UnprocessableContentFields(_) => {
+                "https://developer.mozilla.org/docs/Web/HTTP/Status/422"
+            }
+            PreconditionRequired(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/428",
+            InternalServerError(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/500",
+            ServiceUnavailable(_) => "https://developer.mozilla.org/docs/Web/HTTP/Status/503",
         }
     }
     /// AppErrorが持つ<Detail>を返す（無ければ None）。
@@ -74,9 +151,21 @@ impl AppError {
             | NotFound(d)
             | RequestTimeout(d)
             | Conflict(d)
+            | PayloadTooLarge(d)
             | ImATeapot(d)
+            | TooManyRequests(d)
             | UnprocessableContent(d)
-            | InternalServerError(d) => d.as_ref(),
+            | PreconditionRequired(d)
+            | InternalServerError(d)
+            | ServiceUnavailable(d) => d.as_ref(),
+            UnprocessableContentFields(_) => None,
+        }
+    }
+    /// AppErrorが持つフィールド毎のエラーを返す（無ければ None）。
+    pub fn field_errors(&self) -> Option<&[FieldError]> {
+        match self {
+            UnprocessableContentFields(errors) => Some(errors),
+            _ => None,
         }
     }
 }
@@ -85,40 +174,71 @@ impl IntoResponse for AppError {
     /// AppErrorをaxumの<HTTP Response>に変換する。
     fn into_response(self) -> Response {
         let status = self.status_code();
+        // ログとレスポンスを突合するためのリクエストID
+        let instance = current_request_id();
 
         // ログ出力（500系はerror、それ以外はwarn）
         if status.is_server_error() {
-            error!(?self, "internal server error");
+            error!(?self, request_id = ?instance, "internal server error");
         } else {
-            warn!(?self, "client error");
+            warn!(?self, request_id = ?instance, "client error");
         }
 
         // Statusに応じてResponse Bodyを構築（500系には<Detail>を含めない）
         let body = if status.is_server_error() {
             ApiError {
+                r#type: Some(self.type_uri().to_string()),
                 status: status.as_u16(),
                 message: status
                     .canonical_reason()
                     .unwrap_or("Internal Server Error")
                     .to_string(),
                 detail: None,
-                instance: None,
+                instance,
+                errors: None,
                 timestamp: Utc::now().timestamp(),
             }
         } else {
             ApiError {
+                r#type: Some(self.type_uri().to_string()),
                 status: status.as_u16(),
                 message: status.canonical_reason().unwrap_or("Error").to_string(),
                 detail: self.detail().cloned(),
-                instance: None,
+                instance,
+                errors: self.field_errors().map(<[FieldError]>::to_vec),
                 timestamp: Utc::now().timestamp(),
             }
         };
 
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+
+        // 503/429の場合は<Retry-After>を付与し，クライアントにバックオフさせる。
+        let retry_after = match &self {
+            ServiceUnavailable(_) => Some(retry_after_secs()),
+            TooManyRequests(Some(d)) => d.trim().parse::<u64>().ok(),
+            _ => None,
+        };
+        match retry_after {
+            Some(secs) => with_retry_after(response, secs),
+            None => response,
+        }
     }
 }
 
+/// 一意制約名をクライアント向けのメッセージに変換する（未知の制約名は None）。
+pub fn constraint_to_message(constraint: &str) -> Option<String> {
+    let message = match constraint {
+        "users_user_name_key" => "このユーザー名は既に使用されています。",
+        "users_email_lower_key" => "このメールアドレスは既に使用されています。",
+        "users_phone_key" => "この電話番号は既に使用されています。",
+        _ => return None,
+    };
+    Some(message.into())
+}
+
 /// sqlx のエラーをAppErrorに変換する。
 impl From<SqlxError> for AppError {
     fn from(e: SqlxError) -> Self {
@@ -128,10 +248,19 @@ impl From<SqlxError> for AppError {
         match e {
             SqlxError::RowNotFound => AppError::NotFound(Some("Resource not found".into())),
             SqlxError::PoolTimedOut => AppError::RequestTimeout(Some("Database timeout".into())),
+            SqlxError::PoolClosed => {
+                AppError::ServiceUnavailable(Some("Database unavailable".into()))
+            }
+            SqlxError::Io(ref io_err) if io_err.kind() == ErrorKind::ConnectionRefused => {
+                AppError::ServiceUnavailable(Some("Database unavailable".into()))
+            }
             SqlxError::Database(db_err) => match db_err.code().unwrap_or_default().as_ref() {
-                sqlx_error_code::UNIQUE_VIOLATION => {
-                    AppError::Conflict(Some("Duplicate key".into()))
-                }
+                sqlx_error_code::UNIQUE_VIOLATION => AppError::Conflict(Some(
+                    db_err
+                        .constraint()
+                        .and_then(constraint_to_message)
+                        .unwrap_or_else(|| "Duplicate key".into()),
+                )),
                 sqlx_error_code::FK_VIOLATION => {
                     AppError::Conflict(Some("Foreign-key violation".into()))
                 }
@@ -141,10 +270,27 @@ impl From<SqlxError> for AppError {
                 sqlx_error_code::CHECK_VIOLATION => {
                     AppError::UnprocessableContent(Some("Check violation".into()))
                 }
-                code => AppError::InternalServerError(Some(format!(
-                    "Database error ({code}): {}",
-                    db_err.message()
-                ))),
+                // 同時実行による競合。トランザクション全体を再試行すれば成功し得る。
+                sqlx_error_code::SERIALIZATION_FAILURE | sqlx_error_code::DEADLOCK_DETECTED => {
+                    AppError::Conflict(Some(
+                        "他のリクエストと競合しました。再試行してください。".into(),
+                    ))
+                }
+                // statement_timeoutによるキャンセル。
+                sqlx_error_code::QUERY_CANCELED => AppError::RequestTimeout(Some(
+                    "処理に時間がかかりすぎたため中断しました。時間をおいて再試行してください。"
+                        .into(),
+                )),
+                code => {
+                    // 原因の調査に必要な情報はログにのみ出力し，<Detail>は汎用的なものに留める。
+                    error!(
+                        code,
+                        error = %error_chain(db_err.as_error()),
+                        details = ?db_err,
+                        "Unhandled database error"
+                    );
+                    AppError::InternalServerError(Some(format!("Database error ({code})")))
+                }
             },
             // 文字列に"timeout"が含まれていれば408エラー。
             _ if e_str.contains("timeout") => {
@@ -152,11 +298,34 @@ impl From<SqlxError> for AppError {
             }
 
             // その他不明なエラー。
-            other => AppError::InternalServerError(Some(format!("DB error: {other}"))),
+            other => {
+                error!(
+                    error = %error_chain(&other),
+                    details = ?other,
+                    "Unhandled sqlx error"
+                );
+                AppError::InternalServerError(Some("Database error".into()))
+            }
         }
     }
 }
 
+/// エラーとその<source>を辿り，`outer: inner: ...`の形式で連結する。
+/// sqlxのように表示に<source>を含めるエラーもあるため，重複する部分は連結しない。
+fn error_chain(e: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = e.to_string();
+    let mut source = e.source();
+    while let Some(inner) = source {
+        let message = inner.to_string();
+        if !chain.ends_with(&message) {
+            chain.push_str(": ");
+            chain.push_str(&message);
+        }
+        source = inner.source();
+    }
+    chain
+}
+
 /// パスワードのハッシュ化・検証に関連するエラー。
 #[derive(Debug, Error)]
 pub enum HashingError {
@@ -164,6 +333,24 @@ pub enum HashingError {
     PasswordMismatch,
     #[error("Argon2 error: {0}")]
     Argon2(#[from] Argon2Error),
+    #[error("Hashing task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// パスワードの不一致は401に，Argon2の内部エラーは原因をログに出力した上で500に変換する。
+/// 不一致の<Detail>はユーザーの存在有無を推測されないよう，ユーザー名の誤りと区別しない。
+impl From<HashingError> for AppError {
+    fn from(e: HashingError) -> Self {
+        match e {
+            HashingError::PasswordMismatch => AppError::Unauthorized(Some(
+                "ユーザー名またはパスワードが正しくありません。".into(),
+            )),
+            HashingError::Argon2(_) | HashingError::Task(_) => {
+                error!(error = %e, "Password hashing failed");
+                AppError::InternalServerError(Some("Password hashing failed".into()))
+            }
+        }
+    }
 }
 
 /// ドメイン層で使用されるデータベース関連のエラー。
@@ -174,3 +361,249 @@ pub enum DatabaseError {
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
 }
+
+/// ドメイン層のDatabaseErrorをAppErrorに変換する（sqlxのエラーは既存の変換に委ねる）。
+impl From<DatabaseError> for AppError {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::NotFound => AppError::NotFound(Some("Resource not found".into())),
+            DatabaseError::Sqlx(e) => AppError::from(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Captured;
+    use sqlx::error::{DatabaseError as SqlxDatabaseError, ErrorKind as SqlxErrorKind};
+    use std::{borrow::Cow, fmt, io};
+
+    /// 任意のSQLSTATEコードを持つデータベースエラー。
+    #[derive(Debug)]
+    struct FakeDbError(&'static str);
+
+    impl fmt::Display for FakeDbError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "fake database error ({})", self.0)
+        }
+    }
+
+    impl std::error::Error for FakeDbError {}
+
+    impl SqlxDatabaseError for FakeDbError {
+        fn message(&self) -> &str {
+            "fake database error"
+        }
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+        fn kind(&self) -> SqlxErrorKind {
+            SqlxErrorKind::Other
+        }
+    }
+
+    fn db_error(code: &'static str) -> SqlxError {
+        SqlxError::Database(Box::new(FakeDbError(code)))
+    }
+
+    /// 未知のDBエラーは詳細がログに出力され，<Detail>にはメッセージが含まれないことを確認
+    #[test]
+    fn unknown_database_error_is_logged_in_detail() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .finish();
+        let err =
+            tracing::subscriber::with_default(subscriber, || AppError::from(db_error("XX000")));
+
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.detail().unwrap(), "Database error (XX000)");
+        let output = captured.contents();
+        assert!(output.contains("Unhandled database error"), "{}", output);
+        assert!(output.contains("fake database error (XX000)"), "{}", output);
+    }
+
+    /// <source>を辿ってエラーが連結され，表示に含まれる<source>は重複しないことを確認
+    #[test]
+    fn error_chain_follows_sources() {
+        let err = SqlxError::Io(io::Error::other("socket closed"));
+        assert_eq!(
+            error_chain(&err),
+            "error communicating with database: socket closed"
+        );
+    }
+
+    /// PoolClosedが503に変換され，<Retry-After>が付与されることを確認
+    #[test]
+    fn pool_closed_maps_to_service_unavailable() {
+        let err = AppError::from(SqlxError::PoolClosed);
+        assert!(matches!(err, AppError::ServiceUnavailable(_)));
+
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &retry_after_secs().to_string()
+        );
+    }
+
+    /// statement_timeoutによるキャンセル（57014）が408に変換されることを確認
+    #[test]
+    fn query_canceled_maps_to_request_timeout() {
+        let err = AppError::from(db_error(sqlx_error_code::QUERY_CANCELED));
+        assert!(matches!(err, AppError::RequestTimeout(Some(_))));
+        assert_eq!(err.status_code(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    /// 直列化失敗（40001）とデッドロック（40P01）が再試行を促す409に変換されることを確認
+    #[test]
+    fn serialization_failure_and_deadlock_map_to_conflict() {
+        for code in [
+            sqlx_error_code::SERIALIZATION_FAILURE,
+            sqlx_error_code::DEADLOCK_DETECTED,
+        ] {
+            let err = AppError::from(db_error(code));
+            assert_eq!(err.status_code(), StatusCode::CONFLICT, "{}", code);
+            assert!(
+                err.detail().unwrap().contains("再試行してください"),
+                "{}",
+                code
+            );
+        }
+    }
+
+    /// DatabaseErrorのNotFoundは404に，Sqlxは既存の変換と同じ結果になることを確認
+    #[test]
+    fn database_error_maps_to_app_error() {
+        let err = AppError::from(DatabaseError::NotFound);
+        assert!(matches!(err, AppError::NotFound(Some(_))));
+
+        let err = AppError::from(DatabaseError::from(SqlxError::PoolClosed));
+        assert!(matches!(err, AppError::ServiceUnavailable(_)));
+
+        let err = AppError::from(DatabaseError::from(db_error(
+            sqlx_error_code::SERIALIZATION_FAILURE,
+        )));
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+    }
+
+    /// パスワードの不一致は401に，Argon2の内部エラーは<Detail>を含まない500になることを確認
+    #[tokio::test]
+    async fn hashing_error_maps_to_app_error() {
+        let err = AppError::from(HashingError::PasswordMismatch);
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+        assert!(!err.detail().unwrap().contains("存在"));
+
+        let err = AppError::from(HashingError::Argon2(Argon2Error::SaltInvalid(
+            argon2::password_hash::errors::InvalidValue::TooShort,
+        )));
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!err.detail().unwrap().contains("salt"));
+
+        let bytes = axum::body::to_bytes(err.into_response().into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["status"], 500);
+        assert!(body.get("detail").is_none());
+    }
+
+    /// 接続拒否のIOエラーが503に変換されることを確認
+    #[test]
+    fn connection_refused_maps_to_service_unavailable() {
+        let io_err = std::io::Error::from(ErrorKind::ConnectionRefused);
+        let err = AppError::from(SqlxError::Io(io_err));
+        assert_eq!(err.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    /// TooManyRequestsが429に変換され，秒数の<Detail>が<Retry-After>になることを確認
+    #[test]
+    fn too_many_requests_sets_retry_after() {
+        let response = AppError::TooManyRequests(Some("30".into())).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "30");
+
+        // 秒数として解釈できない<Detail>の場合は付与しない。
+        let response = AppError::TooManyRequests(Some("slow down".into())).into_response();
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    /// `with_retry_after`で<Detail>とは別に<Retry-After>を付与できることを確認
+    #[test]
+    fn with_retry_after_sets_header() {
+        let response = with_retry_after(
+            AppError::TooManyRequests(Some("slow down".into())).into_response(),
+            30,
+        );
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "30");
+    }
+
+    /// 既知の一意制約名が個別のメッセージに変換されることを確認
+    #[test]
+    fn constraint_name_maps_to_message() {
+        assert_eq!(
+            constraint_to_message("users_user_name_key").as_deref(),
+            Some("このユーザー名は既に使用されています。")
+        );
+        assert_eq!(
+            constraint_to_message("users_email_lower_key").as_deref(),
+            Some("このメールアドレスは既に使用されています。")
+        );
+        assert!(constraint_to_message("unknown_constraint").is_none());
+    }
+
+    /// エラーレスポンスがProblem+JSON形式で<type>を含むことを確認
+    #[tokio::test]
+    async fn error_response_is_problem_json() {
+        let response = AppError::NotFound(Some("missing".into())).into_response();
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON
+        );
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["type"], AppError::NotFound(None).type_uri());
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["detail"], "missing");
+        assert!(body.get("errors").is_none());
+    }
+
+    /// 複数のフィールドエラーが<errors>として全て返ることを確認
+    #[tokio::test]
+    async fn field_errors_are_serialized() {
+        let response = AppError::UnprocessableContentFields(vec![
+            FieldError::new("user_name", "ユーザー名を入力してください。"),
+            FieldError::new("email", "メールアドレスの形式が正しくありません。"),
+        ])
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["status"], 422);
+        assert_eq!(
+            body["errors"],
+            serde_json::json!([
+                { "field": "user_name", "message": "ユーザー名を入力してください。" },
+                { "field": "email", "message": "メールアドレスの形式が正しくありません。" },
+            ])
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Idempotency-Keyに対する最初のレスポンスの保存を抽象化するモジュール。
+
+use crate::error::AppResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    sync::{Arc, Mutex},
+};
+
+/// 保存したレスポンスのデフォルトの有効期間（秒）。
+pub const DEFAULT_IDEMPOTENCY_TTL_SECS: i64 = 60 * 60;
+
+/// メモリ上に保持するレコード数のデフォルトの上限。
+/// 保存するボディは`app.max_body_bytes`以下に制限されるため，使用量は概ね両者の積で抑えられる。
+pub const DEFAULT_IDEMPOTENCY_MAX_RECORDS: usize = 10_000;
+
+/// ハンドラ間で共有するIdempotencyStore。
+pub type SharedIdempotencyStore = Arc<dyn IdempotencyStore>;
+
+/// 再送時にそのまま返すためのレスポンス。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Vec<u8>,
+}
+
+/// Idempotency-Keyに紐づくリクエストのハッシュとレスポンス。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    /// 呼出し元とリクエストボディのハッシュ。同じキーで異なるリクエストが送られたことの検出に使用する。
+    pub request_hash: Vec<u8>,
+    /// 保存したレスポンス（最初のリクエストを処理中の間は None）。
+    pub response: Option<StoredResponse>,
+}
+
+/// `(key, route)`毎に最初のレスポンスを保存するストア。
+/// `key`は呼出し元毎に異なる値となるよう，呼出し側で識別子を含めて渡す。
+/// 有効期限切れのレコードは存在しないものとして扱う。
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// 有効なレコードがあればそれを返す。無い場合は処理中のレコードを登録して None を返す。
+    /// 確認と登録は不可分に行い，同時に届いた再送のうち1つだけが None を受け取る。
+    async fn reserve(
+        &self,
+        key: &str,
+        route: &str,
+        request_hash: &[u8],
+    ) -> AppResult<Option<IdempotencyRecord>>;
+    /// 処理中のレコードにレスポンスを保存する。
+    async fn complete(&self, key: &str, route: &str, response: StoredResponse) -> AppResult<()>;
+    /// 処理中のレコードを削除し，同じキーで再試行できるようにする。
+    async fn release(&self, key: &str, route: &str) -> AppResult<()>;
+}
+
+/// `(key, route)`の組。
+type RecordKey = (String, String);
+
+/// メモリ上に保持するIdempotencyStore。
+/// レコード数が`max_records`に達した場合は，有効期限が最も近いものから削除する。
+pub struct MemoryIdempotencyStore {
+    records: Mutex<HashMap<RecordKey, (IdempotencyRecord, DateTime<Utc>)>>,
+    ttl: Duration,
+    max_records: usize,
+}
+
+impl MemoryIdempotencyStore {
+    pub fn new(ttl: Duration, max_records: usize) -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+            ttl,
+            max_records,
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn reserve(
+        &self,
+        key: &str,
+        route: &str,
+        request_hash: &[u8],
+    ) -> AppResult<Option<IdempotencyRecord>> {
+        let now = Utc::now();
+        let mut records = self
+            .records
+            .lock()
+            .expect("idempotency store lock poisoned");
+        // 期限切れのレコードは書き込みの度に取り除き，メモリ使用量を抑える。
+        records.retain(|_, (_, expires_at)| *expires_at > now);
+        let record_key = (key.to_owned(), route.to_owned());
+        if !records.contains_key(&record_key) && records.len() >= self.max_records {
+            let oldest = records
+                .iter()
+                .min_by_key(|(_, (_, expires_at))| *expires_at)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                records.remove(&oldest);
+            }
+        }
+        match records.entry(record_key) {
+            Entry::Occupied(entry) => Ok(Some(entry.get().0.clone())),
+            Entry::Vacant(entry) => {
+                entry.insert((
+                    IdempotencyRecord {
+                        request_hash: request_hash.to_vec(),
+                        response: None,
+                    },
+                    now + self.ttl,
+                ));
+                Ok(None)
+            }
+        }
+    }
+
+    async fn complete(&self, key: &str, route: &str, response: StoredResponse) -> AppResult<()> {
+        let mut records = self
+            .records
+            .lock()
+            .expect("idempotency store lock poisoned");
+        if let Some((record, expires_at)) = records.get_mut(&(key.to_owned(), route.to_owned())) {
+            record.response = Some(response);
+            *expires_at = Utc::now() + self.ttl;
+        }
+        Ok(())
+    }
+
+    async fn release(&self, key: &str, route: &str) -> AppResult<()> {
+        self.records
+            .lock()
+            .expect("idempotency store lock poisoned")
+            .remove(&(key.to_owned(), route.to_owned()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str) -> StoredResponse {
+        StoredResponse {
+            status: 201,
+            headers: vec![],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// 最初の予約のみが処理を許可され，完了後は保存したレスポンスが`(key, route)`の組で取得できることを確認
+    #[tokio::test]
+    async fn reserve_then_complete() {
+        let store = MemoryIdempotencyStore::new(Duration::hours(1), 16);
+        assert_eq!(
+            store.reserve("k1", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+
+        let pending = store.reserve("k1", "/auth/register", &[1]).await.unwrap();
+        assert_eq!(
+            pending,
+            Some(IdempotencyRecord {
+                request_hash: vec![1],
+                response: None,
+            })
+        );
+
+        store
+            .complete("k1", "/auth/register", response("first"))
+            .await
+            .unwrap();
+        let found = store
+            .reserve("k1", "/auth/register", &[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.response, Some(response("first")));
+        assert_eq!(
+            store.reserve("k1", "/auth/login", &[1]).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            store.reserve("k2", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+    }
+
+    /// 上限に達すると有効期限が最も近いレコードから削除されることを確認
+    #[tokio::test]
+    async fn oldest_record_is_evicted_at_capacity() {
+        let store = MemoryIdempotencyStore::new(Duration::hours(1), 2);
+        assert_eq!(
+            store.reserve("k1", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            store.reserve("k2", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            store.reserve("k3", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+
+        assert!(store.records.lock().unwrap().len() <= 2);
+        assert!(
+            store
+                .reserve("k3", "/auth/register", &[1])
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            store
+                .reserve("k2", "/auth/register", &[1])
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    /// 解放したキーは再度予約できることを確認
+    #[tokio::test]
+    async fn released_key_can_be_reserved_again() {
+        let store = MemoryIdempotencyStore::new(Duration::hours(1), 16);
+        assert_eq!(
+            store.reserve("k1", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+        store.release("k1", "/auth/register").await.unwrap();
+        assert_eq!(
+            store.reserve("k1", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+    }
+
+    /// 有効期限切れのレコードは存在しないものとして扱われることを確認
+    #[tokio::test]
+    async fn expired_record_is_ignored() {
+        let store = MemoryIdempotencyStore::new(Duration::zero(), 16);
+        assert_eq!(
+            store.reserve("k1", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+        store
+            .complete("k1", "/auth/register", response("first"))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.reserve("k1", "/auth/register", &[1]).await.unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! 再起動せずにログレベルを変更するためのフィルタを提供するモジュール。
+
+use crate::{
+    config::Logging,
+    error::{AppError, AppResult},
+};
+use tracing::info;
+use tracing_subscriber::{Registry, filter::LevelFilter, reload};
+
+/// 実行中にログレベルを変更するためのハンドル。
+pub type LogLevelHandle = reload::Handle<LevelFilter, Registry>;
+
+/// `initial`で初期化した再読み込み可能なフィルタと，そのハンドルを返す。
+/// フィルタは`tracing_subscriber::registry()`の直後に追加すること。
+pub fn reloadable(initial: LevelFilter) -> (reload::Layer<LevelFilter, Registry>, LogLevelHandle) {
+    reload::Layer::new(initial)
+}
+
+/// ログレベルを`level`に変更し，変更後のレベルを返す。
+/// `Logging::LEVELS`以外の値は`AppError::BadRequest`になる。
+pub fn set_level(handle: &LogLevelHandle, level: &str) -> AppResult<LevelFilter> {
+    let filter = Logging::parse_level(level).ok_or_else(|| {
+        AppError::BadRequest(Some(format!(
+            "ログレベルには{}のいずれかを指定してください。",
+            Logging::LEVELS.join(", ")
+        )))
+    })?;
+    handle.reload(filter).map_err(|e| {
+        AppError::InternalServerError(Some(format!("Failed to reload the log level: {}", e)))
+    })?;
+    info!("Log level changed to {}", filter);
+    Ok(filter)
+}
@@ -0,0 +1,161 @@
+//! ユーザー名毎の連続したログイン失敗を記録し，アカウントをロックするモジュール。
+
+use crate::error::AppResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+/// ハンドラ間で共有するLoginAttemptStore。
+pub type SharedLoginAttemptStore = Arc<dyn LoginAttemptStore>;
+
+/// ユーザー名毎の連続したログイン失敗を記録するストア。
+/// 存在しないユーザー名も同様に扱い，ロックの有無からユーザーの存在が分からないようにする。
+#[async_trait]
+pub trait LoginAttemptStore: Send + Sync {
+    /// ロック中の場合，解除までの残り時間を返す（ロックされていなければ None）。
+    async fn locked_for(&self, user_name: &str) -> AppResult<Option<Duration>>;
+    /// ログインの失敗を記録し，上限に達した場合はロックする。
+    async fn record_failure(&self, user_name: &str) -> AppResult<()>;
+    /// ログインの成功時に失敗回数をリセットする。
+    async fn reset(&self, user_name: &str) -> AppResult<()>;
+}
+
+/// ユーザー名毎の失敗回数とロックの期限。
+#[derive(Debug, Clone, Copy)]
+struct Attempts {
+    failures: u32,
+    last_failed_at: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl Attempts {
+    /// `now`時点で記録を保持する必要があるか（ロック中，または直近`window`以内に失敗している）。
+    fn is_active(&self, now: DateTime<Utc>, window: Duration) -> bool {
+        match self.locked_until {
+            Some(until) => until > now,
+            None => now - self.last_failed_at < window,
+        }
+    }
+}
+
+/// メモリ上に保持するLoginAttemptStore。
+pub struct MemoryLoginAttemptStore {
+    attempts: Mutex<HashMap<String, Attempts>>,
+    max_failures: u32,
+    lockout: Duration,
+}
+
+impl MemoryLoginAttemptStore {
+    /// `max_failures`回連続で失敗した場合に`lockout`の間ロックするストアを作成する。
+    /// `lockout`以上失敗の無いユーザー名は失敗回数がリセットされる。
+    pub fn new(max_failures: u32, lockout: Duration) -> Self {
+        Self {
+            attempts: Mutex::new(HashMap::new()),
+            max_failures,
+            lockout,
+        }
+    }
+
+    fn locked_for_at(&self, user_name: &str, now: DateTime<Utc>) -> Option<Duration> {
+        let attempts = self.attempts.lock().expect("login attempt lock poisoned");
+        attempts
+            .get(user_name)
+            .and_then(|a| a.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    fn record_failure_at(&self, user_name: &str, now: DateTime<Utc>) {
+        let mut attempts = self.attempts.lock().expect("login attempt lock poisoned");
+        // 期限切れの記録は書き込みの度に取り除き，メモリ使用量を抑える。
+        attempts.retain(|_, a| a.is_active(now, self.lockout));
+        let entry = attempts.entry(user_name.to_owned()).or_insert(Attempts {
+            failures: 0,
+            last_failed_at: now,
+            locked_until: None,
+        });
+        entry.failures += 1;
+        entry.last_failed_at = now;
+        if entry.failures >= self.max_failures {
+            entry.locked_until = Some(now + self.lockout);
+        }
+    }
+}
+
+#[async_trait]
+impl LoginAttemptStore for MemoryLoginAttemptStore {
+    async fn locked_for(&self, user_name: &str) -> AppResult<Option<Duration>> {
+        Ok(self.locked_for_at(user_name, Utc::now()))
+    }
+
+    async fn record_failure(&self, user_name: &str) -> AppResult<()> {
+        self.record_failure_at(user_name, Utc::now());
+        Ok(())
+    }
+
+    async fn reset(&self, user_name: &str) -> AppResult<()> {
+        self.attempts
+            .lock()
+            .expect("login attempt lock poisoned")
+            .remove(user_name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> MemoryLoginAttemptStore {
+        MemoryLoginAttemptStore::new(3, Duration::minutes(15))
+    }
+
+    /// 上限に達するまではロックされず，達した時点でロックされることを確認
+    #[test]
+    fn locks_at_threshold() {
+        let store = store();
+        let now = Utc::now();
+        store.record_failure_at("alice", now);
+        store.record_failure_at("alice", now);
+        assert_eq!(store.locked_for_at("alice", now), None);
+
+        store.record_failure_at("alice", now);
+        assert_eq!(
+            store.locked_for_at("alice", now),
+            Some(Duration::minutes(15))
+        );
+        assert_eq!(store.locked_for_at("bob", now), None);
+    }
+
+    /// ロックは期間の経過後に解除され，失敗回数も数え直しになることを確認
+    #[test]
+    fn lock_expires_after_window() {
+        let store = store();
+        let start = Utc::now();
+        for _ in 0..3 {
+            store.record_failure_at("alice", start);
+        }
+        let later = start + Duration::minutes(10);
+        assert_eq!(
+            store.locked_for_at("alice", later),
+            Some(Duration::minutes(5))
+        );
+
+        let after = start + Duration::minutes(15);
+        assert_eq!(store.locked_for_at("alice", after), None);
+        store.record_failure_at("alice", after);
+        assert_eq!(store.locked_for_at("alice", after), None);
+    }
+
+    /// 成功時のリセットで失敗回数が数え直しになることを確認
+    #[tokio::test]
+    async fn reset_clears_failures() {
+        let store = store();
+        store.record_failure("alice").await.unwrap();
+        store.record_failure("alice").await.unwrap();
+        store.reset("alice").await.unwrap();
+        store.record_failure("alice").await.unwrap();
+        store.record_failure("alice").await.unwrap();
+        assert_eq!(store.locked_for("alice").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! 起動時にDBマイグレーションを適用し，その適用状況を確認するモジュール。
+
+use crate::error::{AppError, AppResult};
+use sqlx::{PgPool, migrate::Migrator};
+use tracing::info;
+
+/// ワークスペース直下のmigrationsディレクトリを埋め込んだMigrator。
+pub static MIGRATOR: Migrator = sqlx::migrate!("../../migrations");
+
+/// 未適用のマイグレーションを全て適用する。
+pub async fn run(pool: &PgPool) -> AppResult<()> {
+    MIGRATOR.run(pool).await.map_err(|e| {
+        AppError::InternalServerError(Some(format!("Failed to run database migrations: {}", e)))
+    })?;
+    info!(
+        "Database migrations applied ({} in total)",
+        MIGRATOR.iter().count()
+    );
+    Ok(())
+}
+
+/// 埋め込まれたマイグレーションの適用状況。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// 適用済みのバージョン（昇順）。
+    pub applied: Vec<i64>,
+    /// 未適用のバージョン（昇順）。
+    pub pending: Vec<i64>,
+}
+
+impl MigrationStatus {
+    /// 全てのマイグレーションが適用済みの場合は true を返す。
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// `MIGRATOR`に含まれるマイグレーションと`_sqlx_migrations`を突き合わせる。
+pub async fn status(pool: &PgPool) -> AppResult<MigrationStatus> {
+    // 一度もマイグレーションを適用していないDBにはテーブル自体が存在しない。
+    let table_exists: bool =
+        sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+    let applied_in_db: Vec<i64> = if table_exists {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    let (applied, pending) = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version)
+        .partition(|version| applied_in_db.contains(version));
+    Ok(MigrationStatus { applied, pending })
+}
+
+#[cfg(all(test, feature = "db-tests"))]
+mod tests {
+    use super::*;
+
+    /// 最新のマイグレーションを未適用の状態に戻す。
+    async fn unapply_latest(pool: &PgPool) -> i64 {
+        sqlx::query_scalar(
+            "DELETE FROM _sqlx_migrations WHERE version = (SELECT max(version) FROM _sqlx_migrations) RETURNING version",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// 適用前は全て未適用，適用後は全て適用済みとして報告されることを確認
+    #[sqlx::test(migrations = false)]
+    async fn status_reflects_applied_migrations(pool: PgPool) {
+        let total = MIGRATOR.iter().count();
+        let before = status(&pool).await.unwrap();
+        assert!(before.applied.is_empty());
+        assert_eq!(before.pending.len(), total);
+
+        run(&pool).await.unwrap();
+        let after = status(&pool).await.unwrap();
+        assert!(after.is_up_to_date());
+        assert_eq!(after.applied.len(), total);
+    }
+
+    /// 一部が未適用の場合，そのバージョンがpendingとして報告されることを確認
+    #[sqlx::test(migrations = false)]
+    async fn status_reports_pending_migration(pool: PgPool) {
+        run(&pool).await.unwrap();
+        let latest = unapply_latest(&pool).await;
+
+        let status = status(&pool).await.unwrap();
+        assert!(!status.is_up_to_date());
+        assert_eq!(status.pending, vec![latest]);
+        assert!(!status.applied.contains(&latest));
+    }
+
+    /// 空のDBにマイグレーションを適用すると，各テーブルが作成されることを確認
+    #[sqlx::test(migrations = false)]
+    async fn creates_tables_on_fresh_database(pool: PgPool) {
+        run(&pool).await.unwrap();
+        for table in ["users", "user_auths", "sessions"] {
+            let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+                .bind(table)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert!(exists, "{} was not created", table);
+        }
+
+        // 二回目の適用は何もしない
+        run(&pool).await.unwrap();
+    }
+}
@@ -0,0 +1,10 @@
+pub mod idempotency_store;
+pub mod log_level;
+pub mod login_attempt_store;
+pub mod migrations;
+pub mod repository;
+pub mod retry;
+pub mod session_store;
+pub mod telemetry;
+pub mod tls;
+pub mod tx;
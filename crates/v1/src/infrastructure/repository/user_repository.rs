@@ -0,0 +1,995 @@
+//! ユーザーの永続化を抽象化するモジュール。
+
+use crate::{
+    domain::value_obj::{
+        birth_date::BirthDate, email::Email, normalized_str::NormalizedString,
+        phone_number::PhoneNumber, public_id::PublicId, user_id::UserId, user_name::UserName,
+    },
+    error::{AppError, AppResult, constraint_to_message},
+    infrastructure::tx::with_transaction,
+    presentation::dto::pagination::PageParams,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{FromRow, PgPool};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+/// 一般ユーザーのrole。
+pub const ROLE_USER: i16 = 0;
+/// 管理者のrole。
+pub const ROLE_ADMIN: i16 = 1;
+
+/// ハンドラ間で共有するUserRepository。
+pub type SharedUserRepository = Arc<dyn UserRepository>;
+
+/// 登録するユーザー。各項目は検証済みのVOで受け取る。
+#[derive(Debug, Clone)]
+pub struct NewUser {
+    pub public_id: PublicId,
+    pub randomart: String,
+    pub user_name: UserName,
+    pub first_name: Option<NormalizedString>,
+    pub last_name: Option<NormalizedString>,
+    pub email: Option<Email>,
+    pub phone: Option<PhoneNumber>,
+    pub birth_date: Option<BirthDate>,
+    /// Argon2idでハッシュ化済みのパスワード。
+    pub hashed_password: String,
+}
+
+/// プロフィールの変更内容。各項目は検証済みのVOで受け取る。
+/// 外側の None は変更しないこと，`Some(None)`は値を消去することを表す。
+#[derive(Debug, Clone, Default)]
+pub struct UserChanges {
+    pub first_name: Option<Option<NormalizedString>>,
+    pub last_name: Option<Option<NormalizedString>>,
+    pub email: Option<Option<Email>>,
+    pub phone: Option<Option<PhoneNumber>>,
+    pub birth_date: Option<Option<BirthDate>>,
+}
+
+/// 永続化されたユーザー（usersとuser_authsを結合したもの）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserRecord {
+    pub user_id: UserId,
+    pub public_id: PublicId,
+    pub randomart: String,
+    pub user_name: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub birth_date: Option<NaiveDate>,
+    pub role: i16,
+    /// 楽観的排他制御に使用するバージョン。更新の度に1ずつ増える。
+    pub version: i64,
+    pub hashed_password: String,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// 論理削除された日時（検索結果では常に None）。
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl UserRecord {
+    /// 管理者の場合は true を返す。
+    pub fn is_admin(&self) -> bool {
+        self.role == ROLE_ADMIN
+    }
+}
+
+/// ユーザーの登録・検索を行うリポジトリ。
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// ユーザーとパスワードハッシュを登録し，公開IDを返す。
+    /// 一意制約に違反した場合は`AppError::Conflict`を返す。
+    async fn insert(&self, new_user: NewUser) -> AppResult<PublicId>;
+    /// ユーザー名でユーザーを検索する。
+    async fn find_by_user_name(&self, name: &UserName) -> AppResult<Option<UserRecord>>;
+    /// 公開IDでユーザーを検索する。
+    async fn find_by_public_id(&self, id: &PublicId) -> AppResult<Option<UserRecord>>;
+    /// 内部IDでユーザーを検索する。
+    async fn find_by_user_id(&self, id: UserId) -> AppResult<Option<UserRecord>>;
+    /// 論理削除されていないユーザーを登録順に1ページ分取得し，全体の件数と共に返す。
+    async fn list(&self, page: PageParams) -> AppResult<(Vec<UserRecord>, u64)>;
+    /// 最終ログイン日時を現在時刻に更新する。
+    async fn record_login(&self, id: UserId) -> AppResult<()>;
+    /// パスワードハッシュを更新する。直前までのハッシュは新しいものから`history`件まで履歴として保持し，
+    /// それより古いものは削除する。
+    async fn update_password(
+        &self,
+        id: UserId,
+        hashed_password: &str,
+        history: usize,
+    ) -> AppResult<()>;
+    /// 履歴として保持している過去のパスワードハッシュを新しい順に最大`limit`件返す（現在のものは含まない）。
+    async fn recent_password_hashes(&self, id: UserId, limit: usize) -> AppResult<Vec<String>>;
+    /// バージョンが`expected_version`と一致する場合のみ指定された項目を更新し，更新後のユーザーを返す。
+    /// バージョンが一致しない場合や一意制約に違反した場合は`AppError::Conflict`を返す。
+    async fn update(
+        &self,
+        id: UserId,
+        expected_version: i64,
+        changes: &UserChanges,
+    ) -> AppResult<UserRecord>;
+    /// ユーザーを論理削除する。以降の検索では存在しないものとして扱う。
+    /// 既に削除済みの場合も成功とする。
+    async fn soft_delete(&self, id: UserId) -> AppResult<()>;
+}
+
+/// 更新対象のバージョンが古い場合のエラー。
+fn stale_version() -> AppError {
+    AppError::Conflict(Some(
+        "他の更新と競合しました。最新の情報を取得してから再度実行してください。".into(),
+    ))
+}
+
+/// users及びuser_authsテーブルを使用するUserRepository。
+pub struct PgUserRepository {
+    pool: PgPool,
+}
+
+impl PgUserRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// 論理削除されていないUserRecordを読み込むSELECT文（条件は呼出し側で`AND`で付与する）。
+const SELECT_USER: &str = r#"
+    SELECT u.user_id, u.public_id, u.randomart, u.user_name, u.first_name, u.last_name,
+           u.email, u.phone, u.birth_date, u.role, u.version,
+           a.current_hashed_password AS hashed_password,
+           u.last_login_at, u.created_at, u.updated_at, u.deleted_at
+    FROM users u
+    JOIN user_auths a ON a.user_id = u.user_id
+    WHERE u.deleted_at IS NULL
+"#;
+
+/// DBから読み込んだ行。
+#[derive(FromRow)]
+struct UserRow {
+    user_id: i64,
+    public_id: Uuid,
+    randomart: String,
+    user_name: String,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    birth_date: Option<NaiveDate>,
+    role: i16,
+    version: i64,
+    hashed_password: String,
+    last_login_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<UserRow> for UserRecord {
+    type Error = AppError;
+
+    fn try_from(row: UserRow) -> AppResult<Self> {
+        Ok(Self {
+            user_id: UserId::new(row.user_id)?,
+            public_id: PublicId::from(row.public_id),
+            randomart: row.randomart,
+            user_name: row.user_name,
+            first_name: row.first_name,
+            last_name: row.last_name,
+            email: row.email,
+            phone: row.phone,
+            birth_date: row.birth_date,
+            role: row.role,
+            version: row.version,
+            hashed_password: row.hashed_password,
+            last_login_at: row.last_login_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+#[async_trait]
+impl UserRepository for PgUserRepository {
+    async fn insert(&self, new_user: NewUser) -> AppResult<PublicId> {
+        // usersとuser_authsは同一トランザクションで登録する。
+        let public_id = with_transaction(&self.pool, async |conn| {
+            let (user_id, public_id): (i64, Uuid) = sqlx::query_as(
+                r#"
+                INSERT INTO users (public_id, randomart, user_name, first_name, last_name, email, phone, birth_date)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING user_id, public_id
+                "#,
+            )
+            .bind(new_user.public_id.as_uuid())
+            .bind(&new_user.randomart)
+            .bind(new_user.user_name.as_str())
+            .bind(new_user.first_name.as_ref().map(NormalizedString::as_str))
+            .bind(new_user.last_name.as_ref().map(NormalizedString::as_str))
+            .bind(new_user.email.as_ref().map(Email::as_str))
+            .bind(new_user.phone.as_ref().map(PhoneNumber::as_str))
+            .bind(new_user.birth_date.as_ref().map(BirthDate::as_date))
+            .fetch_one(&mut *conn)
+            .await?;
+
+            sqlx::query(
+                "INSERT INTO user_auths (user_id, current_hashed_password) VALUES ($1, $2)",
+            )
+            .bind(user_id)
+            .bind(&new_user.hashed_password)
+            .execute(&mut *conn)
+            .await?;
+            Ok(public_id)
+        })
+        .await?;
+        Ok(PublicId::from(public_id))
+    }
+
+    async fn find_by_user_name(&self, name: &UserName) -> AppResult<Option<UserRecord>> {
+        sqlx::query_as::<_, UserRow>(&format!("{} AND u.user_name = $1", SELECT_USER))
+            .bind(name.as_str())
+            .fetch_optional(&self.pool)
+            .await?
+            .map(UserRecord::try_from)
+            .transpose()
+    }
+
+    async fn find_by_public_id(&self, id: &PublicId) -> AppResult<Option<UserRecord>> {
+        sqlx::query_as::<_, UserRow>(&format!("{} AND u.public_id = $1", SELECT_USER))
+            .bind(id.as_uuid())
+            .fetch_optional(&self.pool)
+            .await?
+            .map(UserRecord::try_from)
+            .transpose()
+    }
+
+    async fn find_by_user_id(&self, id: UserId) -> AppResult<Option<UserRecord>> {
+        sqlx::query_as::<_, UserRow>(&format!("{} AND u.user_id = $1", SELECT_USER))
+            .bind(id.as_i64())
+            .fetch_optional(&self.pool)
+            .await?
+            .map(UserRecord::try_from)
+            .transpose()
+    }
+
+    async fn list(&self, page: PageParams) -> AppResult<(Vec<UserRecord>, u64)> {
+        let users = sqlx::query_as::<_, UserRow>(&format!(
+            "{} ORDER BY u.user_id LIMIT $1 OFFSET $2",
+            SELECT_USER
+        ))
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(UserRecord::try_from)
+        .collect::<AppResult<Vec<_>>>()?;
+        let total: i64 = sqlx::query_scalar("SELECT count(*) FROM users WHERE deleted_at IS NULL")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok((users, u64::try_from(total).unwrap_or_default()))
+    }
+
+    async fn record_login(&self, id: UserId) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE users SET last_login_at = now() WHERE user_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id.as_i64())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn update_password(
+        &self,
+        id: UserId,
+        hashed_password: &str,
+        history: usize,
+    ) -> AppResult<()> {
+        with_transaction(&self.pool, async |conn| {
+            let previous: String = sqlx::query_scalar(
+                r#"
+                SELECT a.current_hashed_password
+                FROM user_auths a
+                JOIN users u ON u.user_id = a.user_id
+                WHERE a.user_id = $1 AND u.deleted_at IS NULL
+                FOR UPDATE OF a
+                "#,
+            )
+            .bind(id.as_i64())
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(Some("ユーザーが見つかりません。".into())))?;
+
+            sqlx::query(
+                "UPDATE user_auths SET current_hashed_password = $2, updated_at = now() WHERE user_id = $1",
+            )
+            .bind(id.as_i64())
+            .bind(hashed_password)
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("INSERT INTO password_histories (user_id, hashed_password) VALUES ($1, $2)")
+                .bind(id.as_i64())
+                .bind(&previous)
+                .execute(&mut *conn)
+                .await?;
+            // 新しいものから`history`件を残し，それより古い履歴は削除する。
+            sqlx::query(
+                r#"
+                DELETE FROM password_histories
+                WHERE user_id = $1
+                  AND history_id NOT IN (
+                      SELECT history_id FROM password_histories
+                      WHERE user_id = $1
+                      ORDER BY history_id DESC
+                      LIMIT $2
+                  )
+                "#,
+            )
+            .bind(id.as_i64())
+            .bind(i64::try_from(history).unwrap_or(i64::MAX))
+            .execute(&mut *conn)
+            .await?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn recent_password_hashes(&self, id: UserId, limit: usize) -> AppResult<Vec<String>> {
+        let hashes = sqlx::query_scalar(
+            "SELECT hashed_password FROM password_histories WHERE user_id = $1 ORDER BY history_id DESC LIMIT $2",
+        )
+        .bind(id.as_i64())
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(hashes)
+    }
+
+    async fn update(
+        &self,
+        id: UserId,
+        expected_version: i64,
+        changes: &UserChanges,
+    ) -> AppResult<UserRecord> {
+        // 偶数番目のパラメータで変更の有無を，奇数番目で新しい値を渡す。
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET first_name = CASE WHEN $2 THEN $3 ELSE first_name END,
+                last_name = CASE WHEN $4 THEN $5 ELSE last_name END,
+                email = CASE WHEN $6 THEN $7 ELSE email END,
+                phone = CASE WHEN $8 THEN $9 ELSE phone END,
+                birth_date = CASE WHEN $10 THEN $11 ELSE birth_date END,
+                version = version + 1,
+                updated_at = now()
+            WHERE user_id = $1 AND version = $12 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id.as_i64())
+        .bind(changes.first_name.is_some())
+        .bind(
+            changes
+                .first_name
+                .clone()
+                .flatten()
+                .map(NormalizedString::into_inner),
+        )
+        .bind(changes.last_name.is_some())
+        .bind(
+            changes
+                .last_name
+                .clone()
+                .flatten()
+                .map(NormalizedString::into_inner),
+        )
+        .bind(changes.email.is_some())
+        .bind(
+            changes
+                .email
+                .as_ref()
+                .and_then(|e| e.as_ref().map(Email::as_str)),
+        )
+        .bind(changes.phone.is_some())
+        .bind(
+            changes
+                .phone
+                .as_ref()
+                .and_then(|p| p.as_ref().map(PhoneNumber::as_str)),
+        )
+        .bind(changes.birth_date.is_some())
+        .bind(
+            changes
+                .birth_date
+                .as_ref()
+                .and_then(|b| b.as_ref().map(BirthDate::as_date)),
+        )
+        .bind(expected_version)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(match self.find_by_user_id(id).await? {
+                Some(_) => stale_version(),
+                None => AppError::NotFound(Some("ユーザーが見つかりません。".into())),
+            });
+        }
+        self.find_by_user_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(Some("ユーザーが見つかりません。".into())))
+    }
+
+    async fn soft_delete(&self, id: UserId) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE users SET deleted_at = now(), updated_at = now() WHERE user_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id.as_i64())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// メモリ上に保持するUserRepository（テスト用）。
+#[derive(Default)]
+pub struct MemoryUserRepository {
+    users: Mutex<Vec<UserRecord>>,
+    /// ユーザー毎の過去のパスワードハッシュ（新しい順）。
+    password_histories: Mutex<HashMap<UserId, Vec<String>>>,
+}
+
+impl MemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ユーザーのroleを変更する（テストで管理者を用意するためのもの）。
+    pub fn set_role(&self, id: UserId, role: i16) {
+        let mut users = self.users.lock().expect("user repository lock poisoned");
+        if let Some(user) = users.iter_mut().find(|u| u.user_id == id) {
+            user.role = role;
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for MemoryUserRepository {
+    async fn insert(&self, new_user: NewUser) -> AppResult<PublicId> {
+        let mut users = self.users.lock().expect("user repository lock poisoned");
+        let email = new_user.email.as_ref().map(|e| e.as_str().to_owned());
+        let phone = new_user.phone.as_ref().map(|p| p.as_str().to_owned());
+        // Postgresの一意制約と同じ判定を行う。
+        let violated = users.iter().find_map(|u| {
+            if u.user_name == new_user.user_name.as_str() {
+                Some("users_user_name_key")
+            } else if email.is_some() && u.email == email {
+                Some("users_email_lower_key")
+            } else if phone.is_some() && u.phone == phone {
+                Some("users_phone_key")
+            } else {
+                None
+            }
+        });
+        if let Some(constraint) = violated {
+            return Err(AppError::Conflict(constraint_to_message(constraint)));
+        }
+
+        let now = Utc::now();
+        let user_id = UserId::new(users.len() as i64 + 1)?;
+        users.push(UserRecord {
+            user_id,
+            public_id: new_user.public_id,
+            randomart: new_user.randomart,
+            user_name: new_user.user_name.as_str().to_owned(),
+            first_name: new_user.first_name.map(NormalizedString::into_inner),
+            last_name: new_user.last_name.map(NormalizedString::into_inner),
+            email,
+            phone,
+            birth_date: new_user.birth_date.map(|b| *b.as_date()),
+            role: ROLE_USER,
+            version: 1,
+            hashed_password: new_user.hashed_password,
+            last_login_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        });
+        Ok(new_user.public_id)
+    }
+
+    async fn find_by_user_name(&self, name: &UserName) -> AppResult<Option<UserRecord>> {
+        let users = self.users.lock().expect("user repository lock poisoned");
+        Ok(users
+            .iter()
+            .find(|u| u.deleted_at.is_none() && u.user_name == name.as_str())
+            .cloned())
+    }
+
+    async fn find_by_public_id(&self, id: &PublicId) -> AppResult<Option<UserRecord>> {
+        let users = self.users.lock().expect("user repository lock poisoned");
+        Ok(users
+            .iter()
+            .find(|u| u.deleted_at.is_none() && u.public_id == *id)
+            .cloned())
+    }
+
+    async fn find_by_user_id(&self, id: UserId) -> AppResult<Option<UserRecord>> {
+        let users = self.users.lock().expect("user repository lock poisoned");
+        Ok(users
+            .iter()
+            .find(|u| u.deleted_at.is_none() && u.user_id == id)
+            .cloned())
+    }
+
+    async fn list(&self, page: PageParams) -> AppResult<(Vec<UserRecord>, u64)> {
+        let users = self.users.lock().expect("user repository lock poisoned");
+        let active = users.iter().filter(|u| u.deleted_at.is_none());
+        let offset = usize::try_from(page.offset()).unwrap_or(usize::MAX);
+        let limit = usize::try_from(page.limit()).unwrap_or(usize::MAX);
+        Ok((
+            active.clone().skip(offset).take(limit).cloned().collect(),
+            active.count() as u64,
+        ))
+    }
+
+    async fn record_login(&self, id: UserId) -> AppResult<()> {
+        let mut users = self.users.lock().expect("user repository lock poisoned");
+        if let Some(user) = users
+            .iter_mut()
+            .find(|u| u.deleted_at.is_none() && u.user_id == id)
+        {
+            user.last_login_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn update_password(
+        &self,
+        id: UserId,
+        hashed_password: &str,
+        history: usize,
+    ) -> AppResult<()> {
+        let mut users = self.users.lock().expect("user repository lock poisoned");
+        let user = users
+            .iter_mut()
+            .find(|u| u.deleted_at.is_none() && u.user_id == id)
+            .ok_or_else(|| AppError::NotFound(Some("ユーザーが見つかりません。".into())))?;
+        let previous = std::mem::replace(&mut user.hashed_password, hashed_password.to_owned());
+        user.updated_at = Utc::now();
+
+        let mut histories = self
+            .password_histories
+            .lock()
+            .expect("password history lock poisoned");
+        let hashes = histories.entry(id).or_default();
+        hashes.insert(0, previous);
+        hashes.truncate(history);
+        Ok(())
+    }
+
+    async fn recent_password_hashes(&self, id: UserId, limit: usize) -> AppResult<Vec<String>> {
+        let histories = self
+            .password_histories
+            .lock()
+            .expect("password history lock poisoned");
+        Ok(histories
+            .get(&id)
+            .map(|hashes| hashes.iter().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn update(
+        &self,
+        id: UserId,
+        expected_version: i64,
+        changes: &UserChanges,
+    ) -> AppResult<UserRecord> {
+        let mut users = self.users.lock().expect("user repository lock poisoned");
+        let email = changes
+            .email
+            .as_ref()
+            .map(|e| e.as_ref().map(|e| e.as_str().to_owned()));
+        let phone = changes
+            .phone
+            .as_ref()
+            .map(|p| p.as_ref().map(|p| p.as_str().to_owned()));
+        // Postgresの一意制約と同じ判定を行う。
+        let violated = users.iter().filter(|u| u.user_id != id).find_map(|u| {
+            if matches!(&email, Some(Some(e)) if u.email.as_ref() == Some(e)) {
+                Some("users_email_lower_key")
+            } else if matches!(&phone, Some(Some(p)) if u.phone.as_ref() == Some(p)) {
+                Some("users_phone_key")
+            } else {
+                None
+            }
+        });
+        if let Some(constraint) = violated {
+            return Err(AppError::Conflict(constraint_to_message(constraint)));
+        }
+
+        let user = users
+            .iter_mut()
+            .find(|u| u.deleted_at.is_none() && u.user_id == id)
+            .ok_or_else(|| AppError::NotFound(Some("ユーザーが見つかりません。".into())))?;
+        if user.version != expected_version {
+            return Err(stale_version());
+        }
+        if let Some(first_name) = &changes.first_name {
+            user.first_name = first_name.clone().map(NormalizedString::into_inner);
+        }
+        if let Some(last_name) = &changes.last_name {
+            user.last_name = last_name.clone().map(NormalizedString::into_inner);
+        }
+        if let Some(email) = email {
+            user.email = email;
+        }
+        if let Some(phone) = phone {
+            user.phone = phone;
+        }
+        if let Some(birth_date) = &changes.birth_date {
+            user.birth_date = birth_date.as_ref().map(|b| *b.as_date());
+        }
+        user.version += 1;
+        user.updated_at = Utc::now();
+        Ok(user.clone())
+    }
+
+    async fn soft_delete(&self, id: UserId) -> AppResult<()> {
+        let mut users = self.users.lock().expect("user repository lock poisoned");
+        if let Some(user) = users
+            .iter_mut()
+            .find(|u| u.deleted_at.is_none() && u.user_id == id)
+        {
+            let now = Utc::now();
+            user.deleted_at = Some(now);
+            user.updated_at = now;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_user(name: &str, email: Option<&str>) -> NewUser {
+        NewUser {
+            public_id: PublicId::generate(),
+            randomart: "+-----[USER]------+".into(),
+            user_name: UserName::new(name, &[]).unwrap(),
+            first_name: None,
+            last_name: None,
+            email: email.map(|e| Email::new(e, true).unwrap().unwrap()),
+            phone: None,
+            birth_date: None,
+            hashed_password: "$argon2id$dummy".into(),
+        }
+    }
+
+    /// 登録したユーザーをユーザー名と公開IDで検索できることを確認
+    async fn insert_then_find(repo: &dyn UserRepository) {
+        let public_id = repo
+            .insert(new_user("alice", Some("alice@example.com")))
+            .await
+            .unwrap();
+
+        let name = UserName::new("alice", &[]).unwrap();
+        let by_name = repo.find_by_user_name(&name).await.unwrap().unwrap();
+        assert_eq!(by_name.public_id, public_id);
+        assert_eq!(by_name.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(by_name.hashed_password, "$argon2id$dummy");
+
+        let by_id = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        assert_eq!(by_id.user_name, "alice");
+        assert!(!by_id.is_admin());
+        let by_user_id = repo.find_by_user_id(by_id.user_id).await.unwrap().unwrap();
+        assert_eq!(by_user_id.public_id, public_id);
+
+        repo.update_password(by_id.user_id, "$argon2id$updated", 2)
+            .await
+            .unwrap();
+        let updated = repo.find_by_user_id(by_id.user_id).await.unwrap().unwrap();
+        assert_eq!(updated.hashed_password, "$argon2id$updated");
+
+        let unknown = UserName::new("nobody", &[]).unwrap();
+        assert!(repo.find_by_user_name(&unknown).await.unwrap().is_none());
+        let unknown = PublicId::generate();
+        assert!(repo.find_by_public_id(&unknown).await.unwrap().is_none());
+    }
+
+    /// 指定した項目のみ更新され，`Some(None)`で値が消去されることを確認
+    async fn update_changes_only_given_fields(repo: &dyn UserRepository) {
+        let public_id = repo
+            .insert(new_user("alice", Some("alice@example.com")))
+            .await
+            .unwrap();
+        repo.insert(new_user("bob", Some("bob@example.com")))
+            .await
+            .unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+
+        let changes = UserChanges {
+            first_name: Some(NormalizedString::new("Alice", false, None, None).unwrap()),
+            email: Some(None),
+            ..Default::default()
+        };
+        let updated = repo.update(user.user_id, 1, &changes).await.unwrap();
+        assert_eq!(updated.version, 2);
+        assert_eq!(updated.first_name.as_deref(), Some("Alice"));
+        assert_eq!(updated.email, None);
+        assert_eq!(updated.user_name, "alice");
+
+        let changes = UserChanges {
+            email: Some(Email::new("bob@example.com", false).unwrap()),
+            ..Default::default()
+        };
+        let err = repo.update(user.user_id, 2, &changes).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(Some(_))));
+    }
+
+    /// 古いバージョンでの更新は409になり，値が変わらないことを確認
+    async fn stale_version_is_conflict(repo: &dyn UserRepository) {
+        let public_id = repo.insert(new_user("alice", None)).await.unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        assert_eq!(user.version, 1);
+
+        let changes = UserChanges {
+            first_name: Some(NormalizedString::new("Alice", false, None, None).unwrap()),
+            ..Default::default()
+        };
+        repo.update(user.user_id, user.version, &changes)
+            .await
+            .unwrap();
+
+        let stale = UserChanges {
+            first_name: Some(NormalizedString::new("Mallory", false, None, None).unwrap()),
+            ..Default::default()
+        };
+        let err = repo
+            .update(user.user_id, user.version, &stale)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(Some(_))));
+        let current = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        assert_eq!(current.first_name.as_deref(), Some("Alice"));
+        assert_eq!(current.version, 2);
+    }
+
+    /// 論理削除したユーザーは検索できず，再度の削除も成功することを確認
+    async fn soft_deleted_user_is_invisible(repo: &dyn UserRepository) {
+        let public_id = repo.insert(new_user("alice", None)).await.unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+
+        repo.soft_delete(user.user_id).await.unwrap();
+        repo.soft_delete(user.user_id).await.unwrap();
+
+        let name = UserName::new("alice", &[]).unwrap();
+        assert!(repo.find_by_user_name(&name).await.unwrap().is_none());
+        assert!(repo.find_by_public_id(&public_id).await.unwrap().is_none());
+        assert!(repo.find_by_user_id(user.user_id).await.unwrap().is_none());
+        let err = repo
+            .update(user.user_id, user.version, &UserChanges::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
+
+    /// 一覧が登録順にページ分割され，論理削除したユーザーは件数にも含まれないことを確認
+    async fn list_pages(repo: &dyn UserRepository) {
+        for name in ["alice", "bob", "carol", "dave", "erin"] {
+            repo.insert(new_user(name, None)).await.unwrap();
+        }
+        let name = UserName::new("bob", &[]).unwrap();
+        let bob = repo.find_by_user_name(&name).await.unwrap().unwrap();
+        repo.soft_delete(bob.user_id).await.unwrap();
+
+        let names = |users: Vec<UserRecord>| -> Vec<String> {
+            users.into_iter().map(|u| u.user_name).collect()
+        };
+        let page = |page, per_page| PageParams { page, per_page };
+
+        let (users, total) = repo.list(page(1, 3)).await.unwrap();
+        assert_eq!(names(users), ["alice", "carol", "dave"]);
+        assert_eq!(total, 4);
+        let (users, total) = repo.list(page(2, 3)).await.unwrap();
+        assert_eq!(names(users), ["erin"]);
+        assert_eq!(total, 4);
+        let (users, total) = repo.list(page(3, 3)).await.unwrap();
+        assert!(users.is_empty());
+        assert_eq!(total, 4);
+    }
+
+    /// パスワードの変更で直前のハッシュが履歴に残り，`history`件を超えた古いものは削除されることを確認
+    async fn password_history_is_pruned(repo: &dyn UserRepository) {
+        let public_id = repo.insert(new_user("alice", None)).await.unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        assert!(
+            repo.recent_password_hashes(user.user_id, 5)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        for hash in ["$argon2id$1", "$argon2id$2", "$argon2id$3"] {
+            repo.update_password(user.user_id, hash, 2).await.unwrap();
+        }
+        assert_eq!(
+            repo.recent_password_hashes(user.user_id, 5).await.unwrap(),
+            ["$argon2id$2", "$argon2id$1"]
+        );
+        assert_eq!(
+            repo.recent_password_hashes(user.user_id, 1).await.unwrap(),
+            ["$argon2id$2"]
+        );
+
+        repo.update_password(user.user_id, "$argon2id$4", 0)
+            .await
+            .unwrap();
+        assert!(
+            repo.recent_password_hashes(user.user_id, 5)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    /// ユーザー名やメールアドレスの重複が409になることを確認
+    async fn duplicate_is_conflict(repo: &dyn UserRepository) {
+        repo.insert(new_user("alice", Some("alice@example.com")))
+            .await
+            .unwrap();
+        let err = repo.insert(new_user("alice", None)).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(Some(_))));
+        let err = repo
+            .insert(new_user("bob", Some("alice@example.com")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(Some(_))));
+        // 大文字小文字のみが異なるメールアドレスも重複として扱う。
+        let err = repo
+            .insert(new_user("carol", Some("Alice@Example.COM")))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, AppError::Conflict(Some(m)) if m.contains("メールアドレス")),
+            "{:?}",
+            err
+        );
+    }
+
+    /// メモリ上のリポジトリで登録・検索できることを確認
+    #[tokio::test]
+    async fn memory_insert_then_find() {
+        insert_then_find(&MemoryUserRepository::new()).await;
+    }
+
+    /// メモリ上のリポジトリで重複が409になることを確認
+    #[tokio::test]
+    async fn memory_duplicate_is_conflict() {
+        duplicate_is_conflict(&MemoryUserRepository::new()).await;
+    }
+
+    /// メモリ上のリポジトリで指定した項目のみ更新されることを確認
+    #[tokio::test]
+    async fn memory_update_changes_only_given_fields() {
+        update_changes_only_given_fields(&MemoryUserRepository::new()).await;
+    }
+
+    /// メモリ上のリポジトリで古いバージョンでの更新が409になることを確認
+    #[tokio::test]
+    async fn memory_stale_version_is_conflict() {
+        stale_version_is_conflict(&MemoryUserRepository::new()).await;
+    }
+
+    /// メモリ上のリポジトリで論理削除したユーザーが検索できないことを確認
+    #[tokio::test]
+    async fn memory_soft_deleted_user_is_invisible() {
+        soft_deleted_user_is_invisible(&MemoryUserRepository::new()).await;
+    }
+
+    /// メモリ上のリポジトリで一覧がページ分割されることを確認
+    #[tokio::test]
+    async fn memory_list_pages() {
+        list_pages(&MemoryUserRepository::new()).await;
+    }
+
+    /// メモリ上のリポジトリでパスワードの履歴が保持・削除されることを確認
+    #[tokio::test]
+    async fn memory_password_history_is_pruned() {
+        password_history_is_pruned(&MemoryUserRepository::new()).await;
+    }
+
+    /// 最終ログイン日時が更新されることを確認
+    #[tokio::test]
+    async fn memory_record_login() {
+        let repo = MemoryUserRepository::new();
+        let public_id = repo.insert(new_user("alice", None)).await.unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        assert!(user.last_login_at.is_none());
+        repo.record_login(user.user_id).await.unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        assert!(user.last_login_at.is_some());
+    }
+
+    /// Postgres上のリポジトリで登録・検索できることを確認（テストごとに独立したDBを使用）
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_insert_then_find(pool: PgPool) {
+        insert_then_find(&PgUserRepository::new(pool)).await;
+    }
+
+    /// Postgres上のリポジトリで重複が409になることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_duplicate_is_conflict(pool: PgPool) {
+        duplicate_is_conflict(&PgUserRepository::new(pool)).await;
+    }
+
+    /// VOを経由せずに書き込まれた場合も，大文字小文字のみが異なるメールアドレスは一意制約に違反することを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_lower_email_index_rejects_case_variant(pool: PgPool) {
+        PgUserRepository::new(pool.clone())
+            .insert(new_user("alice", Some("alice@example.com")))
+            .await
+            .unwrap();
+        let err: AppError = sqlx::query(
+            "INSERT INTO users (public_id, randomart, user_name, email) VALUES (gen_random_uuid(), '', 'bob', 'ALICE@example.com')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap_err()
+        .into();
+        assert_eq!(
+            err.detail().map(String::as_str),
+            Some("このメールアドレスは既に使用されています。")
+        );
+    }
+
+    /// Postgres上のリポジトリで指定した項目のみ更新されることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_update_changes_only_given_fields(pool: PgPool) {
+        update_changes_only_given_fields(&PgUserRepository::new(pool)).await;
+    }
+
+    /// Postgres上で論理削除したユーザーは検索できないが，行は残ることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_soft_deleted_user_is_invisible(pool: PgPool) {
+        soft_deleted_user_is_invisible(&PgUserRepository::new(pool.clone())).await;
+        let (count, deleted): (i64, i64) =
+            sqlx::query_as("SELECT count(*), count(deleted_at) FROM users")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!((count, deleted), (1, 1));
+    }
+
+    /// Postgres上のリポジトリで一覧がページ分割されることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_list_pages(pool: PgPool) {
+        list_pages(&PgUserRepository::new(pool)).await;
+    }
+
+    /// Postgres上のリポジトリでパスワードの履歴が保持・削除されることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_password_history_is_pruned(pool: PgPool) {
+        password_history_is_pruned(&PgUserRepository::new(pool)).await;
+    }
+
+    /// Postgres上のリポジトリで古いバージョンでの更新が409になることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_stale_version_is_conflict(pool: PgPool) {
+        stale_version_is_conflict(&PgUserRepository::new(pool)).await;
+    }
+}
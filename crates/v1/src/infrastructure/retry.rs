@@ -0,0 +1,94 @@
+//! 一時的な失敗が想定される処理を指数バックオフで再試行するヘルパー。
+
+use std::{fmt::Display, time::Duration};
+use tracing::warn;
+
+/// 再試行までの待ち時間の上限。
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// `f`を実行し，失敗した場合は最大`retries`回再試行する。
+/// n回目の再試行の前に`base_delay * 2^(n-1)`（最大`MAX_BACKOFF`）待つ。
+/// 全て失敗した場合は最後のエラーを返す。
+pub async fn retry_with_backoff<F, T, E>(
+    operation: &str,
+    retries: u32,
+    base_delay: Duration,
+    mut f: F,
+) -> Result<T, E>
+where
+    F: AsyncFnMut() -> Result<T, E>,
+    E: Display,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < retries => {
+                let delay = backoff(base_delay, attempt);
+                attempt += 1;
+                warn!(
+                    "Failed to {} (attempt {}/{}): {}, retrying in {:?}",
+                    operation,
+                    attempt,
+                    retries + 1,
+                    e,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// `attempt`回目（0始まり）の再試行までの待ち時間を返す。
+fn backoff(base_delay: Duration, attempt: u32) -> Duration {
+    base_delay
+        .checked_mul(2u32.saturating_pow(attempt))
+        .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `failures`回失敗した後に成功する処理を実行し，結果と実行回数を返す。
+    async fn run(retries: u32, failures: u32) -> (Result<u32, String>, u32) {
+        let mut calls = 0;
+        let result = retry_with_backoff("connect", retries, Duration::from_millis(1), async || {
+            calls += 1;
+            if calls <= failures {
+                Err(format!("transient failure {}", calls))
+            } else {
+                Ok(calls)
+            }
+        })
+        .await;
+        (result, calls)
+    }
+
+    /// 一時的な失敗の後に成功した場合，その値が返ることを確認
+    #[tokio::test]
+    async fn succeeds_after_transient_failures() {
+        assert_eq!(run(5, 0).await, (Ok(1), 1));
+        assert_eq!(run(5, 3).await, (Ok(4), 4));
+    }
+
+    /// 再試行回数を使い切った場合は最後のエラーが返ることを確認
+    #[tokio::test]
+    async fn gives_up_after_retries() {
+        assert_eq!(run(2, 5).await, (Err("transient failure 3".into()), 3));
+        assert_eq!(run(0, 1).await, (Err("transient failure 1".into()), 1));
+    }
+
+    /// 待ち時間が倍々に増え，上限を超えないことを確認
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff(base, 0), Duration::from_millis(500));
+        assert_eq!(backoff(base, 1), Duration::from_secs(1));
+        assert_eq!(backoff(base, 3), Duration::from_secs(4));
+        assert_eq!(backoff(base, 10), MAX_BACKOFF);
+        assert_eq!(backoff(base, u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,300 @@
+//! セッションの永続化を抽象化するモジュール。
+
+use crate::{
+    domain::value_obj::{session_id::SessionId, user_id::UserId},
+    error::AppResult,
+    infrastructure::tx::with_transaction,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+/// セッションのデフォルトの有効期間（時間）。
+pub const DEFAULT_SESSION_TTL_HOURS: i64 = 24;
+
+/// ハンドラ間で共有するSessionStore。
+pub type SharedSessionStore = Arc<dyn SessionStore>;
+
+/// セッションの作成・取得・失効を行うストア。
+/// 有効期限切れのセッションは存在しないものとして扱う。
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// ユーザーのセッションを作成し，そのIDを返す。
+    async fn create(&self, user_id: UserId) -> AppResult<SessionId>;
+    /// 有効なセッションに紐づくユーザーIDを返す（無効・期限切れなら None）。
+    async fn get(&self, id: &SessionId) -> AppResult<Option<UserId>>;
+    /// セッションを失効させる（存在しない場合も成功とする）。
+    async fn revoke(&self, id: &SessionId) -> AppResult<()>;
+    /// ユーザーの全てのセッションを失効させ，失効させた件数を返す。
+    async fn revoke_all_for_user(&self, user_id: UserId) -> AppResult<u64>;
+    /// `keep`以外のユーザーのセッションを全て失効させ，失効させた件数を返す。
+    async fn revoke_all_except(&self, user_id: UserId, keep: &SessionId) -> AppResult<u64>;
+    /// 有効なセッションを失効させ，同じユーザーの新しいセッションのIDを返す（無効・期限切れなら None）。
+    async fn rotate(&self, id: &SessionId) -> AppResult<Option<SessionId>>;
+}
+
+/// sessionsテーブルを使用するSessionStore。
+pub struct PgSessionStore {
+    pool: PgPool,
+    ttl: Duration,
+}
+
+impl PgSessionStore {
+    pub fn new(pool: PgPool, ttl: Duration) -> Self {
+        Self { pool, ttl }
+    }
+}
+
+#[async_trait]
+impl SessionStore for PgSessionStore {
+    async fn create(&self, user_id: UserId) -> AppResult<SessionId> {
+        let id = SessionId::generate();
+        sqlx::query("INSERT INTO sessions (session_id, user_id, expires_at) VALUES ($1, $2, $3)")
+            .bind(id.as_str())
+            .bind(user_id.as_i64())
+            .bind(Utc::now() + self.ttl)
+            .execute(&self.pool)
+            .await?;
+        Ok(id)
+    }
+
+    async fn get(&self, id: &SessionId) -> AppResult<Option<UserId>> {
+        let user_id: Option<i64> = sqlx::query_scalar(
+            "SELECT user_id FROM sessions WHERE session_id = $1 AND expires_at > now()",
+        )
+        .bind(id.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+        user_id.map(UserId::new).transpose()
+    }
+
+    async fn revoke(&self, id: &SessionId) -> AppResult<()> {
+        sqlx::query("DELETE FROM sessions WHERE session_id = $1")
+            .bind(id.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: UserId) -> AppResult<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+            .bind(user_id.as_i64())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn revoke_all_except(&self, user_id: UserId, keep: &SessionId) -> AppResult<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE user_id = $1 AND session_id <> $2")
+            .bind(user_id.as_i64())
+            .bind(keep.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn rotate(&self, id: &SessionId) -> AppResult<Option<SessionId>> {
+        let new_id = SessionId::generate();
+        let expires_at = Utc::now() + self.ttl;
+        // 同じセッションで同時に更新された場合，DELETEできた一方のみが新しいセッションを得る。
+        with_transaction(&self.pool, async move |conn| {
+            let user_id: Option<i64> = sqlx::query_scalar(
+                "DELETE FROM sessions WHERE session_id = $1 AND expires_at > now() RETURNING user_id",
+            )
+            .bind(id.as_str())
+            .fetch_optional(&mut *conn)
+            .await?;
+            let Some(user_id) = user_id else {
+                return Ok(None);
+            };
+            sqlx::query(
+                "INSERT INTO sessions (session_id, user_id, expires_at) VALUES ($1, $2, $3)",
+            )
+            .bind(new_id.as_str())
+            .bind(user_id)
+            .bind(expires_at)
+            .execute(&mut *conn)
+            .await?;
+            Ok(Some(new_id))
+        })
+        .await
+    }
+}
+
+/// メモリ上に保持するSessionStore（テスト用）。
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<SessionId, (UserId, DateTime<Utc>)>>,
+    ttl: Duration,
+}
+
+impl MemorySessionStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn create(&self, user_id: UserId) -> AppResult<SessionId> {
+        let id = SessionId::generate();
+        let expires_at = Utc::now() + self.ttl;
+        self.sessions
+            .lock()
+            .expect("session store lock poisoned")
+            .insert(id.clone(), (user_id, expires_at));
+        Ok(id)
+    }
+
+    async fn get(&self, id: &SessionId) -> AppResult<Option<UserId>> {
+        let sessions = self.sessions.lock().expect("session store lock poisoned");
+        Ok(sessions
+            .get(id)
+            .filter(|(_, expires_at)| *expires_at > Utc::now())
+            .map(|(user_id, _)| *user_id))
+    }
+
+    async fn revoke(&self, id: &SessionId) -> AppResult<()> {
+        self.sessions
+            .lock()
+            .expect("session store lock poisoned")
+            .remove(id);
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: UserId) -> AppResult<u64> {
+        let mut sessions = self.sessions.lock().expect("session store lock poisoned");
+        let before = sessions.len();
+        sessions.retain(|_, (owner, _)| *owner != user_id);
+        Ok((before - sessions.len()) as u64)
+    }
+
+    async fn revoke_all_except(&self, user_id: UserId, keep: &SessionId) -> AppResult<u64> {
+        let mut sessions = self.sessions.lock().expect("session store lock poisoned");
+        let before = sessions.len();
+        sessions.retain(|id, (owner, _)| *owner != user_id || id == keep);
+        Ok((before - sessions.len()) as u64)
+    }
+
+    async fn rotate(&self, id: &SessionId) -> AppResult<Option<SessionId>> {
+        let mut sessions = self.sessions.lock().expect("session store lock poisoned");
+        let Some((user_id, _)) = sessions
+            .remove(id)
+            .filter(|(_, expires_at)| *expires_at > Utc::now())
+        else {
+            return Ok(None);
+        };
+        let new_id = SessionId::generate();
+        sessions.insert(new_id.clone(), (user_id, Utc::now() + self.ttl));
+        Ok(Some(new_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: i64) -> UserId {
+        UserId::new(id).unwrap()
+    }
+
+    /// 作成したセッションからユーザーIDを取得できることを確認
+    #[tokio::test]
+    async fn create_then_get() {
+        let store = MemorySessionStore::new(Duration::hours(1));
+        let id = store.create(user(1)).await.unwrap();
+        assert_eq!(store.get(&id).await.unwrap(), Some(user(1)));
+        assert_eq!(store.get(&SessionId::generate()).await.unwrap(), None);
+    }
+
+    /// 失効させたセッションは取得できないことを確認
+    #[tokio::test]
+    async fn revoke_removes_session() {
+        let store = MemorySessionStore::new(Duration::hours(1));
+        let id = store.create(user(1)).await.unwrap();
+        store.revoke(&id).await.unwrap();
+        assert_eq!(store.get(&id).await.unwrap(), None);
+        // 存在しないセッションの失効も成功する。
+        assert!(store.revoke(&id).await.is_ok());
+    }
+
+    /// 指定したユーザーのセッションのみ全て失効することを確認
+    #[tokio::test]
+    async fn revoke_all_for_user_keeps_other_users() {
+        let store = MemorySessionStore::new(Duration::hours(1));
+        let mine = [
+            store.create(user(1)).await.unwrap(),
+            store.create(user(1)).await.unwrap(),
+            store.create(user(1)).await.unwrap(),
+        ];
+        let other = store.create(user(2)).await.unwrap();
+
+        assert_eq!(store.revoke_all_for_user(user(1)).await.unwrap(), 3);
+        for id in &mine {
+            assert_eq!(store.get(id).await.unwrap(), None);
+        }
+        assert_eq!(store.get(&other).await.unwrap(), Some(user(2)));
+        assert_eq!(store.revoke_all_for_user(user(1)).await.unwrap(), 0);
+    }
+
+    /// 指定したセッション以外が失効することを確認
+    #[tokio::test]
+    async fn revoke_all_except_keeps_current() {
+        let store = MemorySessionStore::new(Duration::hours(1));
+        let current = store.create(user(1)).await.unwrap();
+        let old = store.create(user(1)).await.unwrap();
+        assert_eq!(store.revoke_all_except(user(1), &current).await.unwrap(), 1);
+        assert_eq!(store.get(&current).await.unwrap(), Some(user(1)));
+        assert_eq!(store.get(&old).await.unwrap(), None);
+    }
+
+    /// 有効期限切れのセッションは存在しないものとして扱われることを確認
+    #[tokio::test]
+    async fn expired_session_is_absent() {
+        let store = MemorySessionStore::new(Duration::zero());
+        let id = store.create(user(1)).await.unwrap();
+        assert_eq!(store.get(&id).await.unwrap(), None);
+    }
+
+    /// 更新すると同じユーザーの新しいセッションが作成され，元のセッションは失効することを確認
+    #[tokio::test]
+    async fn rotate_replaces_session() {
+        let store = MemorySessionStore::new(Duration::hours(1));
+        let old = store.create(user(1)).await.unwrap();
+        let new = store.rotate(&old).await.unwrap().unwrap();
+        assert_ne!(new, old);
+        assert_eq!(store.get(&new).await.unwrap(), Some(user(1)));
+        assert_eq!(store.get(&old).await.unwrap(), None);
+        // 失効したセッションは再度更新できない。
+        assert_eq!(store.rotate(&old).await.unwrap(), None);
+    }
+
+    /// 有効期限切れのセッションは更新できないことを確認
+    #[tokio::test]
+    async fn expired_session_cannot_rotate() {
+        let store = MemorySessionStore::new(Duration::zero());
+        let id = store.create(user(1)).await.unwrap();
+        assert_eq!(store.rotate(&id).await.unwrap(), None);
+    }
+
+    /// sessionsテーブル上でも更新後は新しいセッションのみ有効になることを確認
+    #[cfg(feature = "db-tests")]
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn pg_rotate_replaces_session(pool: PgPool) {
+        let user_id: i64 = sqlx::query_scalar(
+            "INSERT INTO users (public_id, randomart, user_name) VALUES (gen_random_uuid(), '', 'alice') RETURNING user_id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let store = PgSessionStore::new(pool, Duration::hours(1));
+        let old = store.create(user(user_id)).await.unwrap();
+        let new = store.rotate(&old).await.unwrap().unwrap();
+        assert_eq!(store.get(&new).await.unwrap(), Some(user(user_id)));
+        assert_eq!(store.get(&old).await.unwrap(), None);
+        assert_eq!(store.rotate(&old).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,154 @@
+//! OpenTelemetry（OTLP/HTTP）でトレースをエクスポートするモジュール。
+
+use crate::error::{AppError, AppResult};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{SdkTracer, SdkTracerProvider},
+};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// エクスポートするトレースに付与するサービス名。
+pub const SERVICE_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// OTLPのトレースを受け付けるパス。
+const TRACES_PATH: &str = "/v1/traces";
+
+/// `endpoint`（例: `http://localhost:4318`）のコレクタへバッチでエクスポートするTracerProviderを作成する。
+/// 終了時は`shutdown`を呼び，未送信のspanを送信すること。
+pub fn tracer_provider(endpoint: &str) -> AppResult<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpJson)
+        .with_endpoint(traces_url(endpoint))
+        .build()
+        .map_err(|e| {
+            AppError::InternalServerError(Some(format!("Failed to build OTLP exporter: {}", e)))
+        })?;
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build())
+}
+
+/// tracingのspanをOpenTelemetryのspanとして記録するLayerを返す。
+pub fn layer<S>(provider: &SdkTracerProvider) -> OpenTelemetryLayer<S, SdkTracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+}
+
+/// コレクタのURLにトレースのパスを付与する。
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(TRACES_PATH) {
+        endpoint.to_owned()
+    } else {
+        format!("{}{}", endpoint, TRACES_PATH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::middleware::{
+        access_log::access_log_middleware,
+        request_id::{X_REQUEST_ID, request_id_middleware},
+    };
+    use axum::{Router, body::Body, extract::Request, middleware, routing::get, routing::post};
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// 受信したOTLPのJSONを保持するコレクタを起動し，そのURLを返す。
+    async fn mock_collector() -> (String, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let app = Router::new().route(
+            TRACES_PATH,
+            post(move |body: String| {
+                let sink = sink.clone();
+                async move {
+                    sink.lock().unwrap().push(body);
+                    "{}"
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", address), received)
+    }
+
+    /// コレクタのURLにトレースのパスが一度だけ付与されることを確認
+    #[test]
+    fn traces_path_is_appended_once() {
+        assert_eq!(
+            traces_url("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("http://localhost:4318/v1/traces"),
+            "http://localhost:4318/v1/traces"
+        );
+    }
+
+    /// リクエストのspanがリクエストIDとルートを持ってコレクタにエクスポートされることを確認
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_span_is_exported() {
+        let (endpoint, received) = mock_collector().await;
+        let provider = tracer_provider(&endpoint).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer(&provider));
+
+        let app = Router::new()
+            .route("/users/{id}", get(|| async { "ok" }))
+            .layer(middleware::from_fn(access_log_middleware))
+            .layer(middleware::from_fn(request_id_middleware));
+        let request = Request::builder()
+            .uri("/users/42")
+            .header(X_REQUEST_ID, "trace-test-id")
+            .body(Body::empty())
+            .unwrap();
+        {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            app.oneshot(request).await.unwrap();
+        }
+        // エクスポートはバックグラウンドのスレッドで行われるため，ブロッキング可能なスレッドで待つ。
+        tokio::task::spawn_blocking(move || provider.shutdown())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        let span = received
+            .iter()
+            .map(|body| serde_json::from_str::<serde_json::Value>(body).unwrap())
+            .flat_map(|body| {
+                body["resourceSpans"][0]["scopeSpans"][0]["spans"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .find(|span| span["name"] == "request")
+            .expect("request span was not exported");
+        let attribute = |key: &str| {
+            span["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|a| a["key"] == key)
+                .map(|a| a["value"]["stringValue"].clone())
+        };
+        assert_eq!(attribute("request_id").unwrap(), "trace-test-id");
+        assert_eq!(attribute("route").unwrap(), "/users/{id}");
+    }
+}
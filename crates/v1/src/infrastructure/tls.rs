@@ -0,0 +1,63 @@
+//! TLS終端に使用する証明書・秘密鍵を読み込むモジュール。
+
+use crate::error::{AppError, AppResult};
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::Path;
+
+/// PEM形式の証明書チェーンと秘密鍵を読み込み，rustlsの設定を生成する。
+/// ファイルが存在しない，またはPEMとして解釈できない場合はエラーを返す。
+pub async fn load_rustls_config(cert_path: &Path, key_path: &Path) -> AppResult<RustlsConfig> {
+    // 暗号プロバイダ（ring）を登録する（登録済みの場合は何もしない）。
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    for path in [cert_path, key_path] {
+        if !path.is_file() {
+            return Err(AppError::InternalServerError(Some(format!(
+                "TLS file not found: {:?}",
+                path
+            ))));
+        }
+    }
+    RustlsConfig::from_pem_file(cert_path, key_path)
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(Some(format!(
+                "Failed to load TLS certificate {:?} / key {:?}: {}",
+                cert_path, key_path, e
+            )))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/tls")
+            .join(name)
+    }
+
+    /// 自己署名証明書からTLSの設定を生成できることを確認
+    #[tokio::test]
+    async fn loads_self_signed_fixture() {
+        let result = load_rustls_config(&fixture("cert.pem"), &fixture("key.pem")).await;
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
+    /// ファイルが存在しない場合はエラーになることを確認
+    #[tokio::test]
+    async fn missing_file_is_rejected() {
+        let result = load_rustls_config(&fixture("missing.pem"), &fixture("key.pem")).await;
+        assert!(matches!(result, Err(AppError::InternalServerError(_))));
+    }
+
+    /// PEMとして解釈できない場合はエラーになることを確認
+    #[tokio::test]
+    async fn unparseable_pem_is_rejected() {
+        // 証明書を秘密鍵として読み込ませる
+        let result = load_rustls_config(&fixture("cert.pem"), &fixture("cert.pem")).await;
+        assert!(matches!(result, Err(AppError::InternalServerError(_))));
+    }
+}
@@ -0,0 +1,90 @@
+//! 複数の書き込みを1つのトランザクションで実行するためのヘルパー。
+
+use crate::error::AppResult;
+use sqlx::{PgConnection, PgPool};
+use tracing::warn;
+
+/// トランザクションを開始して`f`を実行し，Ok の場合はコミット，Err の場合はロールバックする。
+/// sqlxのエラーは`From<sqlx::Error> for AppError`で変換される。
+pub async fn with_transaction<F, T>(pool: &PgPool, f: F) -> AppResult<T>
+where
+    F: AsyncFnOnce(&mut PgConnection) -> AppResult<T>,
+{
+    let mut tx = pool.begin().await?;
+    match f(&mut tx).await {
+        Ok(value) => {
+            tx.commit().await?;
+            Ok(value)
+        }
+        Err(e) => {
+            // ロールバックに失敗しても，元のエラーを優先して返す。
+            if let Err(rollback_err) = tx.rollback().await {
+                warn!("Failed to roll back transaction: {}", rollback_err);
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "db-tests"))]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+
+    async fn insert_user(conn: &mut PgConnection, name: &str) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO users (public_id, randomart, user_name) VALUES (gen_random_uuid(), '', $1)",
+        )
+        .bind(name)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    async fn count_users(pool: &PgPool) -> i64 {
+        sqlx::query_scalar("SELECT count(*) FROM users")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Ok の場合は全ての書き込みがコミットされることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn commits_on_success(pool: PgPool) {
+        let value = with_transaction(&pool, async |conn| {
+            insert_user(conn, "alice").await?;
+            insert_user(conn, "bob").await?;
+            Ok(42)
+        })
+        .await
+        .unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(count_users(&pool).await, 2);
+    }
+
+    /// Err の場合はそれまでの書き込みがロールバックされ，元のエラーが返ることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn rolls_back_on_error(pool: PgPool) {
+        let err = with_transaction(&pool, async |conn| {
+            insert_user(conn, "alice").await?;
+            Err::<(), _>(AppError::Conflict(Some("abort".into())))
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(Some(ref d)) if d == "abort"));
+        assert_eq!(count_users(&pool).await, 0);
+    }
+
+    /// sqlxのエラーもAppErrorに変換され，ロールバックされることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn rolls_back_on_sqlx_error(pool: PgPool) {
+        let err = with_transaction(&pool, async |conn| {
+            insert_user(conn, "alice").await?;
+            insert_user(conn, "alice").await
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+        assert_eq!(count_users(&pool).await, 0);
+    }
+}
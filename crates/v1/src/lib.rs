@@ -1,4 +1,8 @@
 pub mod config;
 pub mod domain;
 pub mod error;
+pub mod infrastructure;
 pub mod presentation;
+
+#[cfg(test)]
+mod test_support;
@@ -1,23 +1,50 @@
-use chrono::NaiveDate;
+use crate::{
+    domain::value_obj::{
+        birth_date::BirthDate, email::Email, normalized_str::NormalizedString, password::Password,
+        phone_number::PhoneNumber, session_id::SessionId, user_name::UserName,
+    },
+    error::AppResult,
+    presentation::dto::common_dto::{FieldError, FieldErrors},
+};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// 電話番号が国内形式で入力された場合に付与する国番号。
+pub const DEFAULT_COUNTRY_CODE: &str = "81";
+/// 氏名の最大長（usersテーブルの定義に合わせる）。
+pub const NAME_MAX_LEN: usize = 64;
+/// Debug出力でパスワードの代わりに表示する文字列。
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub struct AuthRequest {
     pub user_name: String,
     pub password: String,
 }
 
-#[derive(Debug, Serialize)]
+/// ログに平文のパスワードが出力されないよう，passwordを伏せる。
+impl fmt::Debug for AuthRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthRequest")
+            .field("user_name", &self.user_name)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct AuthResponse {
     pub public_id: String,
-    pub session_id: String,
+    #[schema(value_type = String)]
+    pub session_id: SessionId,
     pub randomart: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub struct RegisterRequest {
     pub user_name: String,
     pub password: String,
@@ -25,12 +52,276 @@ pub struct RegisterRequest {
     pub last_name: Option<String>,
     pub email: Option<String>,
     pub phone: Option<String>,
-    pub birth_date: Option<NaiveDate>,
+    pub birth_date: Option<String>,
+}
+
+/// ログに平文のパスワードが出力されないよう，passwordを伏せる。
+/// 個人情報であるemailとphoneは検証前の値のため，文字列のまま伏せる。
+impl fmt::Debug for RegisterRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegisterRequest")
+            .field("user_name", &self.user_name)
+            .field("password", &REDACTED)
+            .field("first_name", &self.first_name)
+            .field("last_name", &self.last_name)
+            .field("email", &self.email.as_deref().map(Email::mask))
+            .field("phone", &self.phone.as_deref().map(PhoneNumber::mask))
+            .field("birth_date", &self.birth_date)
+            .finish()
+    }
+}
+
+/// 全てのフィールドの検証を通過した登録内容。
+#[derive(Debug)]
+pub struct ValidatedRegistration {
+    pub user_name: UserName,
+    pub password: Password,
+    pub first_name: Option<NormalizedString>,
+    pub last_name: Option<NormalizedString>,
+    pub email: Option<Email>,
+    pub phone: Option<PhoneNumber>,
+    pub birth_date: Option<BirthDate>,
+}
+
+impl RegisterRequest {
+    /// 全てのフィールドをVOで検証する。
+    /// 最初のエラーで打ち切らず，不正な項目を全て返す。
+    pub fn validate(&self) -> Result<ValidatedRegistration, Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+        let user_name = errors.check(
+            "user_name",
+            UserName::new(&self.user_name, UserName::DEFAULT_RESERVED),
+        );
+        let password = errors.check(
+            "password",
+            Password::new(
+                self.password.as_str(),
+                user_name.as_ref().map(UserName::as_str),
+            ),
+        );
+        let first_name = errors
+            .check("first_name", optional_name(self.first_name.as_deref()))
+            .flatten();
+        let last_name = errors
+            .check("last_name", optional_name(self.last_name.as_deref()))
+            .flatten();
+        let email = errors
+            .check(
+                "email",
+                optional(self.email.as_deref(), |v| Email::new(v, false)),
+            )
+            .flatten();
+        let phone = errors
+            .check(
+                "phone",
+                optional(self.phone.as_deref(), |v| {
+                    PhoneNumber::new(v, false, DEFAULT_COUNTRY_CODE)
+                }),
+            )
+            .flatten();
+        let birth_date = errors
+            .check(
+                "birth_date",
+                optional(self.birth_date.as_deref(), |v| BirthDate::new(v, false)),
+            )
+            .flatten();
+
+        match (user_name, password) {
+            (Some(user_name), Some(password)) if errors.is_empty() => Ok(ValidatedRegistration {
+                user_name,
+                password,
+                first_name,
+                last_name,
+                email,
+                phone,
+                birth_date,
+            }),
+            _ => Err(errors.into()),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
+/// 任意入力の氏名を検証する。
+pub(crate) fn optional_name(input: Option<&str>) -> AppResult<Option<NormalizedString>> {
+    optional(input, |name| {
+        NormalizedString::new(name, false, None, Some(NAME_MAX_LEN))
+    })
+}
+
+/// 任意入力の値が指定されている場合のみVOで検証する。
+pub(crate) fn optional<T>(
+    input: Option<&str>,
+    parse: impl FnOnce(&str) -> AppResult<Option<T>>,
+) -> AppResult<Option<T>> {
+    input.map(parse).transpose().map(Option::flatten)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct RegisterResponse {
     pub public_id: String,
     pub randomart: String,
 }
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// ログに平文のパスワードが出力されないよう，両方のパスワードを伏せる。
+impl fmt::Debug for ChangePasswordRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChangePasswordRequest")
+            .field("current_password", &REDACTED)
+            .field("new_password", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ChangePasswordResponse {
+    /// Number of other sessions that were revoked.
+    pub revoked_sessions: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LogoutAllResponse {
+    /// Number of sessions that were revoked.
+    pub revoked: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RefreshResponse {
+    /// Session that replaces the one used for the request.
+    #[schema(value_type = String)]
+    pub session_id: SessionId,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MeResponse {
+    pub public_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> RegisterRequest {
+        RegisterRequest {
+            user_name: "alice".into(),
+            password: "Correct-Horse-42".into(),
+            first_name: Some("Alice".into()),
+            last_name: None,
+            email: Some("alice@example.com".into()),
+            phone: Some("090-1234-5678".into()),
+            birth_date: Some("2000-01-01".into()),
+        }
+    }
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    /// 全ての項目が正しい場合は検証済みの値が返ることを確認
+    #[test]
+    fn valid_request_passes() {
+        let validated = request().validate().unwrap();
+        assert_eq!(validated.user_name.as_str(), "alice");
+        assert!(validated.email.is_some());
+        assert!(validated.phone.is_some());
+        assert!(validated.last_name.is_none());
+    }
+
+    /// 複数の項目が同時に不正な場合，全ての項目が報告されることを確認
+    #[test]
+    fn every_invalid_field_is_reported() {
+        let req = RegisterRequest {
+            user_name: "".into(),
+            password: "short".into(),
+            first_name: Some("x".repeat(NAME_MAX_LEN + 1)),
+            last_name: None,
+            email: Some("not-an-email".into()),
+            phone: Some("abc".into()),
+            birth_date: Some("2000-13-40".into()),
+        };
+        let errors = req.validate().unwrap_err();
+        assert_eq!(
+            fields(&errors),
+            [
+                "user_name",
+                "password",
+                "first_name",
+                "email",
+                "phone",
+                "birth_date"
+            ]
+        );
+        assert!(errors.iter().all(|e| !e.message.is_empty()));
+    }
+
+    /// 未知のフィールドはデシリアライズ時に拒否され，正しい形のボディは受け付けられることを確認
+    #[test]
+    fn unknown_field_is_rejected() {
+        let err = serde_json::from_str::<AuthRequest>(r#"{"username":"alice","password":"x"}"#)
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("unknown field `username`"),
+            "{}",
+            err
+        );
+
+        let req: AuthRequest =
+            serde_json::from_str(r#"{"user_name":"alice","password":"x"}"#).unwrap();
+        assert_eq!(req.user_name, "alice");
+    }
+
+    /// 任意項目のみが不正な場合もエラーになることを確認
+    #[test]
+    fn optional_field_error_is_reported() {
+        let req = RegisterRequest {
+            email: Some("not-an-email".into()),
+            ..request()
+        };
+        assert_eq!(fields(&req.validate().unwrap_err()), ["email"]);
+    }
+
+    /// Debug出力にパスワードが含まれず，メールアドレスと電話番号は伏せられ，ユーザー名は含まれることを確認
+    #[test]
+    fn debug_redacts_password() {
+        let register = format!("{:?}", request());
+        assert!(register.contains("alice"), "{}", register);
+        assert!(register.contains("[REDACTED]"), "{}", register);
+        assert!(!register.contains("Correct-Horse-42"), "{}", register);
+        assert!(!register.contains("alice@example.com"), "{}", register);
+        assert!(register.contains("a***@example.com"), "{}", register);
+        assert!(!register.contains("090-1234-5678"), "{}", register);
+        assert!(!register.contains("1234"), "{}", register);
+
+        let login = format!(
+            "{:?}",
+            AuthRequest {
+                user_name: "alice".into(),
+                password: "Correct-Horse-42".into(),
+            }
+        );
+        assert!(login.contains("alice"), "{}", login);
+        assert!(!login.contains("Correct-Horse-42"), "{}", login);
+
+        let change = format!(
+            "{:?}",
+            ChangePasswordRequest {
+                current_password: "Correct-Horse-42".into(),
+                new_password: "Battery-Staple-43".into(),
+            }
+        );
+        assert!(!change.contains("Correct-Horse-42"), "{}", change);
+        assert!(!change.contains("Battery-Staple-43"), "{}", change);
+    }
+}
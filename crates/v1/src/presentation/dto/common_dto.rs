@@ -1,8 +1,31 @@
 /// Defines the standard format for API responses.
+use crate::{
+    error::{AppError, AppResult},
+    presentation::middleware::request_id::current_request_id,
+};
+use once_cell::sync::OnceCell;
 use serde::Serialize;
+use std::collections::HashMap;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// 起動時にConfigから設定されるAPIのバージョン。
+static API_VERSION: OnceCell<String> = OnceCell::new();
+
+/// レスポンスのmetaに含めるAPIのバージョンを設定する（起動時に一度だけ有効）。
+pub fn set_api_version(version: &str) {
+    if API_VERSION.set(version.to_string()).is_err() {
+        warn!("API version is already set, ignoring {}", version);
+    }
+}
+
+/// 設定されたAPIのバージョンを返す（未設定の場合は None）。
+pub fn api_version() -> Option<&'static str> {
+    API_VERSION.get().map(String::as_str)
+}
 
 /// Successful response structure.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ApiResponse<T> {
     /// The actual response data.
     pub data: T,
@@ -10,11 +33,41 @@ pub struct ApiResponse<T> {
     pub message: String,
     /// The time the response was generated (UNIX timestamp).
     pub timestamp: i64,
+    /// Request metadata such as the request ID and API version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
+    /// Related resources keyed by relation name (e.g. "self", "next").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<HashMap<String, String>>,
 }
 
-/// Error response structure.
-#[derive(Debug, Serialize)]
+/// Metadata attached to a successful response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ResponseMeta {
+    /// The ID of the request (same as the X-Request-Id response header).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// The version of the API that produced the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+}
+
+impl ResponseMeta {
+    /// 処理中のリクエストIDと設定されたAPIのバージョンからmetaを生成する。
+    pub fn current() -> Self {
+        Self {
+            request_id: current_request_id(),
+            api_version: api_version().map(str::to_owned),
+        }
+    }
+}
+
+/// Error response structure (compatible with RFC 7807 Problem Details).
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ApiError {
+    /// A URI reference that identifies the problem type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
     /// HTTP status code corresponding to the error.
     pub status: u16,
     /// A short, human-readable summary of the error.
@@ -25,6 +78,77 @@ pub struct ApiError {
     /// An optional URI or identifier of the instance where the error occurred.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instance: Option<String>,
+    /// Validation errors for individual request fields.
+    #[serde(skip_serializing_if = "no_field_errors")]
+    pub errors: Option<Vec<FieldError>>,
     /// The time the error response was generated (UNIX timestamp).
     pub timestamp: i64,
 }
+
+fn no_field_errors(errors: &Option<Vec<FieldError>>) -> bool {
+    errors.as_ref().is_none_or(Vec::is_empty)
+}
+
+/// A validation error for a single request field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the offending field (e.g. "email").
+    pub field: String,
+    /// Human-readable reason the value was rejected.
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// 複数のフィールドの検証エラーを集め，まとめて422として返すためのビルダー。
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `result`がエラーの場合は`field`のエラーとして記録し，None を返す。
+    pub fn check<T>(&mut self, field: &str, result: AppResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                let message = e.detail().cloned().unwrap_or_else(|| e.to_string());
+                self.push(field, message);
+                None
+            }
+        }
+    }
+
+    /// `field`のエラーを記録する。
+    pub fn push(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, message));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// エラーが1つも無ければ Ok，あれば`AppError::UnprocessableContentFields`を返す。
+    pub fn into_result(self) -> AppResult<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::UnprocessableContentFields(self.0))
+        }
+    }
+}
+
+impl From<FieldErrors> for Vec<FieldError> {
+    fn from(errors: FieldErrors) -> Self {
+        errors.0
+    }
+}
@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct HealthResponse {
+    pub db: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct LivenessResponse {
+    pub status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ReadinessResponse {
+    pub db: &'static str,
+    /// `SELECT 1`の応答時間（ミリ秒）。
+    pub latency_ms: f64,
+    pub size: u32,
+    pub num_idle: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrationsResponse {
+    pub applied: Vec<i64>,
+    pub pending: Vec<i64>,
+}
@@ -1,3 +1,8 @@
+pub mod admin;
 pub mod auth;
 pub mod common_dto;
+pub mod health;
+pub mod pagination;
 pub mod response_helper;
+pub mod user;
+pub mod version;
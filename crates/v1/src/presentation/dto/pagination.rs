@@ -0,0 +1,246 @@
+//! Pagination parameters and response bodies for list endpoints.
+
+use crate::error::{AppError, AppResult};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Default page number (1-based).
+pub const DEFAULT_PAGE: u32 = 1;
+/// Default number of items per page.
+pub const DEFAULT_PER_PAGE: u32 = 20;
+/// Maximum number of items per page.
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Offset pagination parameters read from the query string (`?page=2&per_page=50`).
+/// Extract with `ValidatedQuery<PageParams>` so malformed values produce a 422 envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Page number (1-based).
+    #[serde(default = "default_page")]
+    #[param(default = 1, minimum = 1)]
+    pub page: u32,
+    /// Number of items per page (at most 100).
+    #[serde(default = "default_per_page")]
+    #[param(default = 20, minimum = 1, maximum = 100)]
+    pub per_page: u32,
+}
+
+fn default_page() -> u32 {
+    DEFAULT_PAGE
+}
+
+fn default_per_page() -> u32 {
+    DEFAULT_PER_PAGE
+}
+
+impl Default for PageParams {
+    fn default() -> Self {
+        Self {
+            page: DEFAULT_PAGE,
+            per_page: DEFAULT_PER_PAGE,
+        }
+    }
+}
+
+impl PageParams {
+    /// ページ番号と件数が範囲内か検証する。
+    pub fn validate(&self) -> AppResult<()> {
+        if self.page == 0 {
+            return Err(AppError::BadRequest(Some(
+                "pageは1以上を指定してください。".into(),
+            )));
+        }
+        if self.per_page == 0 || self.per_page > MAX_PER_PAGE {
+            return Err(AppError::BadRequest(Some(format!(
+                "per_pageは1以上{}以下を指定してください。",
+                MAX_PER_PAGE
+            ))));
+        }
+        Ok(())
+    }
+
+    /// SQLのLIMITに使用する件数を返す。
+    pub fn limit(&self) -> i64 {
+        i64::from(self.per_page)
+    }
+
+    /// SQLのOFFSETに使用する件数を返す。
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page.saturating_sub(1)) * i64::from(self.per_page)
+    }
+}
+
+/// A single page of results.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Paginated<T> {
+    /// Items on the current page.
+    pub data: Vec<T>,
+    /// Current page number (1-based).
+    pub page: u32,
+    /// Number of items per page.
+    pub per_page: u32,
+    /// Total number of items across all pages.
+    pub total: u64,
+    /// Total number of pages.
+    pub total_pages: u64,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(data: Vec<T>, params: &PageParams, total: u64) -> Self {
+        Self {
+            data,
+            page: params.page,
+            per_page: params.per_page,
+            total,
+            total_pages: total.div_ceil(u64::from(params.per_page.max(1))),
+        }
+    }
+}
+
+/// Cursor pagination parameters read from the query string (`?after=<cursor>&limit=50`).
+/// Extract with `ValidatedQuery<CursorParams>`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CursorParams {
+    /// Opaque cursor returned as `next_cursor` by the previous page.
+    #[serde(default)]
+    pub after: Option<String>,
+    #[serde(default = "default_per_page")]
+    pub limit: u32,
+}
+
+impl CursorParams {
+    /// 件数を検証し，カーソルを復号して返す（先頭ページの場合は None）。
+    pub fn validate(&self) -> AppResult<Option<Cursor>> {
+        if self.limit == 0 || self.limit > MAX_PER_PAGE {
+            return Err(AppError::BadRequest(Some(format!(
+                "limitは1以上{}以下を指定してください。",
+                MAX_PER_PAGE
+            ))));
+        }
+        self.after.as_deref().map(decode_cursor).transpose()
+    }
+}
+
+/// A page of results fetched with a cursor.
+#[derive(Debug, Serialize)]
+pub struct CursorPage<T> {
+    /// Items on the current page.
+    pub data: Vec<T>,
+    /// Cursor for the next page (`None` on the last page).
+    pub next_cursor: Option<String>,
+}
+
+/// 最後に返した行の位置。`(created_at, id)`の順に並べた一覧で，この行より後を取得する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+/// カーソルをbase64url形式の文字列に変換する。
+pub fn encode_cursor(cursor: &Cursor) -> String {
+    let raw = format!("{}:{}", cursor.created_at.timestamp_micros(), cursor.id);
+    URL_SAFE_NO_PAD.encode(raw)
+}
+
+/// base64url形式の文字列をカーソルに変換する（不正な形式はBadRequest）。
+pub fn decode_cursor(input: &str) -> AppResult<Cursor> {
+    let invalid = || AppError::BadRequest(Some("カーソルの形式が正しくありません。".into()));
+    let bytes = URL_SAFE_NO_PAD.decode(input).map_err(|_| invalid())?;
+    let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (micros, id) = raw.split_once(':').ok_or_else(invalid)?;
+    let created_at = micros
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::from_timestamp_micros)
+        .ok_or_else(invalid)?;
+    let id = id.parse::<i64>().map_err(|_| invalid())?;
+    Ok(Cursor { created_at, id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Query, http::Uri};
+
+    fn parse(uri: &'static str) -> PageParams {
+        Query::<PageParams>::try_from_uri(&Uri::from_static(uri))
+            .unwrap()
+            .0
+    }
+
+    /// クエリが無い場合はpage=1，per_page=20になることを確認
+    #[test]
+    fn defaults_when_absent() {
+        let params = parse("/users");
+        assert_eq!(params, PageParams::default());
+        assert_eq!((params.limit(), params.offset()), (20, 0));
+        assert!(params.validate().is_ok());
+    }
+
+    /// per_pageの上限を超える場合はBadRequestになることを確認
+    #[test]
+    fn per_page_is_capped() {
+        assert!(parse("/users?per_page=100").validate().is_ok());
+        let err = parse("/users?per_page=101").validate().unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+        assert!(parse("/users?page=0").validate().is_err());
+    }
+
+    /// カーソルの変換と復元で同じ値になることを確認
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = Cursor {
+            created_at: DateTime::from_timestamp_micros(1_750_000_000_123_456).unwrap(),
+            id: 42,
+        };
+        let encoded = encode_cursor(&cursor);
+        assert!(!encoded.contains(['+', '/', '=']));
+        assert_eq!(decode_cursor(&encoded).unwrap(), cursor);
+    }
+
+    /// 不正なカーソルはBadRequestになることを確認
+    #[test]
+    fn malformed_cursor_is_rejected() {
+        for input in [
+            "",
+            "!!!",
+            &URL_SAFE_NO_PAD.encode("abc"),
+            &URL_SAFE_NO_PAD.encode("1:x"),
+        ] {
+            assert!(
+                matches!(decode_cursor(input), Err(AppError::BadRequest(_))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    /// CursorParamsのデフォルト値とlimitの上限を確認
+    #[test]
+    fn cursor_params_validate() {
+        let params = Query::<CursorParams>::try_from_uri(&Uri::from_static("/users"))
+            .unwrap()
+            .0;
+        assert_eq!(params.limit, DEFAULT_PER_PAGE);
+        assert_eq!(params.validate().unwrap(), None);
+        let params = CursorParams {
+            after: None,
+            limit: MAX_PER_PAGE + 1,
+        };
+        assert!(params.validate().is_err());
+    }
+
+    /// total_pagesが切り上げで計算されることを確認
+    #[test]
+    fn total_pages_rounds_up() {
+        let params = parse("/users?page=3&per_page=10");
+        assert_eq!(params.offset(), 20);
+        assert_eq!(Paginated::<u8>::new(vec![], &params, 0).total_pages, 0);
+        assert_eq!(Paginated::<u8>::new(vec![], &params, 10).total_pages, 1);
+        assert_eq!(Paginated::<u8>::new(vec![], &params, 21).total_pages, 3);
+    }
+}
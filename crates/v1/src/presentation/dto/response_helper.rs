@@ -1,17 +1,179 @@
 //! Helpers for successful API responses.
 
-use crate::presentation::dto::common_dto::ApiResponse;
-use axum::{Json, http::StatusCode, response::IntoResponse};
+use crate::presentation::dto::{
+    common_dto::{ApiResponse, ResponseMeta},
+    pagination::{PageParams, Paginated},
+};
+use axum::{
+    Json,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
 use chrono::Utc;
 use serde::Serialize;
+use std::collections::HashMap;
 
 /// Wraps any serializable payload into a unified success envelope.
-pub fn api_ok<T: Serialize>(data: T, message: Option<&str>) -> impl IntoResponse {
-    let message = message.unwrap_or("success").to_string();
-    let body = ApiResponse {
+pub fn api_ok<T: Serialize>(
+    data: T,
+    message: Option<&str>,
+    meta: Option<ResponseMeta>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(envelope(data, message, meta, None)))
+}
+
+/// Wraps a newly created resource into the success envelope with 201 and a Location header.
+pub fn api_created<T: Serialize>(
+    data: T,
+    location: &str,
+    message: Option<&str>,
+    meta: Option<ResponseMeta>,
+) -> impl IntoResponse + use<T> {
+    (
+        StatusCode::CREATED,
+        [(header::LOCATION, location.to_string())],
+        Json(envelope(data, message, meta, None)),
+    )
+}
+
+/// Same as `api_ok`, with an `ETag` header.
+/// Returns 304 Not Modified without a body when `If-None-Match` in `request_headers` matches `etag`.
+pub fn api_ok_with_etag<T: Serialize>(
+    request_headers: &HeaderMap,
+    etag: &str,
+    data: T,
+    message: Option<&str>,
+    meta: Option<ResponseMeta>,
+) -> Response {
+    let etag_header = [(header::ETAG, etag.to_string())];
+    if if_none_match(request_headers, etag) {
+        return (StatusCode::NOT_MODIFIED, etag_header).into_response();
+    }
+    (etag_header, api_ok(data, message, meta)).into_response()
+}
+
+/// Builds a strong ETag from a record version (e.g. `"3"`), usable as-is in `If-Match`.
+pub fn version_etag(version: i64) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Returns true when `If-None-Match` contains `etag` or `*` (weak comparison, RFC 9110 13.1.2).
+pub fn if_none_match(request_headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let etag = opaque(etag);
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// Same as `api_ok`, with links to related resources.
+pub fn api_ok_with_links<T: Serialize>(
+    data: T,
+    message: Option<&str>,
+    meta: Option<ResponseMeta>,
+    links: HashMap<String, String>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(envelope(data, message, meta, Some(links))),
+    )
+}
+
+fn envelope<T>(
+    data: T,
+    message: Option<&str>,
+    meta: Option<ResponseMeta>,
+    links: Option<HashMap<String, String>>,
+) -> ApiResponse<T> {
+    ApiResponse {
         data,
-        message,
+        message: message.unwrap_or("success").to_string(),
         timestamp: Utc::now().timestamp(),
-    };
-    (StatusCode::OK, Json(body))
+        meta,
+        links: links.filter(|l| !l.is_empty()),
+    }
+}
+
+/// Wraps one page of items and its pagination info into the success envelope.
+/// The response does not borrow `params`, so it may be built from a local value.
+pub fn api_page<'a, T: Serialize>(
+    data: Vec<T>,
+    params: &PageParams,
+    total: u64,
+    message: Option<&'a str>,
+    meta: Option<ResponseMeta>,
+) -> impl IntoResponse + use<'a, T> {
+    api_ok(Paginated::new(data, params, total), message, meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_json<T: Serialize>(body: ApiResponse<T>) -> serde_json::Value {
+        serde_json::to_value(body).unwrap()
+    }
+
+    /// 201と<Location>が返ることを確認
+    #[test]
+    fn created_sets_status_and_location() {
+        let response = api_created("x", "/users/abc", None, None).into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::LOCATION], "/users/abc");
+    }
+
+    fn if_none_match_headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+        headers
+    }
+
+    /// <If-None-Match>が無い・一致しない場合はETag付きの200になることを確認
+    #[test]
+    fn etag_is_set_on_ok() {
+        let etag = version_etag(3);
+        assert_eq!(etag, "\"3\"");
+        for headers in [HeaderMap::new(), if_none_match_headers("\"2\"")] {
+            let response = api_ok_with_etag(&headers, &etag, "x", None, None);
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[header::ETAG], "\"3\"");
+        }
+    }
+
+    /// <If-None-Match>が一致する場合（弱いETag・複数指定・`*`を含む）は304になることを確認
+    #[test]
+    fn matching_if_none_match_is_not_modified() {
+        let etag = version_etag(3);
+        for value in ["\"3\"", "W/\"3\"", "\"1\", \"3\"", "*"] {
+            let response = api_ok_with_etag(&if_none_match_headers(value), &etag, "x", None, None);
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "{}", value);
+            assert_eq!(response.headers()[header::ETAG], "\"3\"");
+        }
+    }
+
+    /// metaとlinksが無い場合はフィールド自体が出力されないことを確認
+    #[test]
+    fn meta_and_links_are_omitted_when_empty() {
+        let json = to_json(envelope("x", None, None, Some(HashMap::new())));
+        assert!(json.get("meta").is_none());
+        assert!(json.get("links").is_none());
+        assert_eq!(json["message"], "success");
+    }
+
+    /// metaとlinksを指定した場合は出力されることを確認
+    #[test]
+    fn meta_and_links_are_serialized_when_set() {
+        let meta = ResponseMeta {
+            request_id: Some("req-1".into()),
+            api_version: Some("1.2.3".into()),
+        };
+        let links = HashMap::from([("self".to_string(), "/users?page=2".to_string())]);
+        let json = to_json(envelope("x", Some("ok"), Some(meta), Some(links)));
+        assert_eq!(json["meta"]["request_id"], "req-1");
+        assert_eq!(json["meta"]["api_version"], "1.2.3");
+        assert_eq!(json["links"]["self"], "/users?page=2");
+    }
 }
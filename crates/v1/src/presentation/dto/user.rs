@@ -0,0 +1,236 @@
+use crate::{
+    domain::value_obj::{
+        birth_date::BirthDate, email::Email, phone_number::PhoneNumber, public_id::PublicId,
+    },
+    error::AppResult,
+    infrastructure::repository::user_repository::{UserChanges, UserRecord},
+    presentation::dto::{
+        auth::{DEFAULT_COUNTRY_CODE, optional, optional_name},
+        common_dto::{FieldError, FieldErrors},
+    },
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+/// A user as exposed to clients. Never contains the password hash or the internal ID.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct UserResponse {
+    #[schema(value_type = String, format = Uuid)]
+    pub public_id: PublicId,
+    pub user_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub birth_date: Option<NaiveDate>,
+    /// Current version; send it back via `If-Match` or `version` when updating.
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How `UserResponse::email` is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailVisibility {
+    /// The full address, for the user themselves.
+    Full,
+    /// Only the first character of the local part (e.g. `j***@example.com`).
+    Masked,
+}
+
+impl UserResponse {
+    /// `email`の指定に従ってメールアドレスを伏せたUserResponseを作成する。
+    pub fn new(user: UserRecord, email: EmailVisibility) -> Self {
+        Self {
+            public_id: user.public_id,
+            user_name: user.user_name,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            email: match email {
+                EmailVisibility::Full => user.email,
+                EmailVisibility::Masked => user.email.as_deref().map(Email::mask),
+            },
+            phone: user.phone,
+            birth_date: user.birth_date,
+            version: user.version,
+            created_at: user.created_at,
+        }
+    }
+}
+
+impl From<UserRecord> for UserResponse {
+    fn from(user: UserRecord) -> Self {
+        Self::new(user, EmailVisibility::Full)
+    }
+}
+
+/// Partial profile update. An absent field is left unchanged; an explicit `null` clears it.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateUserRequest {
+    /// Expected current version. Required unless the `If-Match` header is given, which takes precedence.
+    #[serde(default)]
+    pub version: Option<i64>,
+    #[serde(default, deserialize_with = "present")]
+    pub first_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub last_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub phone: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub birth_date: Option<Option<String>>,
+}
+
+/// キーが存在する場合は`null`であっても Some として読み込む。
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl UpdateUserRequest {
+    /// 指定された項目のみをVOで検証し，不正な項目を全て返す。
+    pub fn validate(&self) -> Result<UserChanges, Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+        let changes = UserChanges {
+            first_name: errors
+                .check(
+                    "first_name",
+                    changed(&self.first_name, |v| optional_name(Some(v))),
+                )
+                .flatten(),
+            last_name: errors
+                .check(
+                    "last_name",
+                    changed(&self.last_name, |v| optional_name(Some(v))),
+                )
+                .flatten(),
+            email: errors
+                .check("email", changed(&self.email, |v| Email::new(v, false)))
+                .flatten(),
+            phone: errors
+                .check(
+                    "phone",
+                    changed(&self.phone, |v| {
+                        PhoneNumber::new(v, false, DEFAULT_COUNTRY_CODE)
+                    }),
+                )
+                .flatten(),
+            birth_date: errors
+                .check(
+                    "birth_date",
+                    changed(&self.birth_date, |v| BirthDate::new(v, false)),
+                )
+                .flatten(),
+        };
+        if errors.is_empty() {
+            Ok(changes)
+        } else {
+            Err(errors.into())
+        }
+    }
+}
+
+/// 指定された項目のみをVOで検証する（`null`は値の消去として扱う）。
+fn changed<T>(
+    input: &Option<Option<String>>,
+    parse: impl FnOnce(&str) -> AppResult<Option<T>>,
+) -> AppResult<Option<Option<T>>> {
+    input
+        .as_ref()
+        .map(|v| optional(v.as_deref(), parse))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_obj::user_id::UserId;
+
+    fn record() -> UserRecord {
+        let now = Utc::now();
+        UserRecord {
+            user_id: UserId::new(987_654_321).unwrap(),
+            public_id: PublicId::generate(),
+            randomart: String::new(),
+            user_name: "alice".into(),
+            first_name: Some("Alice".into()),
+            last_name: None,
+            email: Some("alice@example.com".into()),
+            phone: None,
+            birth_date: NaiveDate::from_ymd_opt(2000, 1, 1),
+            role: 0,
+            version: 1,
+            hashed_password: "$argon2id$v=19$secret-hash".into(),
+            last_login_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    /// シリアライズ結果にパスワードハッシュや内部IDが含まれないことを確認
+    #[test]
+    fn never_exposes_hash_or_internal_id() {
+        for visibility in [EmailVisibility::Full, EmailVisibility::Masked] {
+            let json = serde_json::to_value(UserResponse::new(record(), visibility)).unwrap();
+            let text = json.to_string();
+            assert!(!text.contains("argon2"), "{}", text);
+            assert!(!text.contains("987654321"), "{}", text);
+            for key in ["user_id", "id", "hashed_password", "password", "role"] {
+                assert!(json.get(key).is_none(), "{}: {}", key, text);
+            }
+            assert_eq!(json["user_name"], "alice");
+            assert_eq!(json["birth_date"], "2000-01-01");
+        }
+    }
+
+    /// 指定に応じてメールアドレスが伏せられることを確認
+    #[test]
+    fn email_is_masked_on_request() {
+        let full = UserResponse::from(record());
+        assert_eq!(full.email.as_deref(), Some("alice@example.com"));
+        let masked = UserResponse::new(record(), EmailVisibility::Masked);
+        assert_eq!(masked.email.as_deref(), Some("a***@example.com"));
+    }
+
+    /// 省略した項目は変更せず，`null`は消去として読み込まれることを確認
+    #[test]
+    fn absent_and_null_are_distinguished() {
+        let req: UpdateUserRequest =
+            serde_json::from_str(r#"{"first_name":"Alice","email":null}"#).unwrap();
+        assert_eq!(req.first_name, Some(Some("Alice".into())));
+        assert_eq!(req.email, Some(None));
+        assert_eq!(req.phone, None);
+
+        let changes = req.validate().unwrap();
+        assert!(matches!(changes.first_name, Some(Some(_))));
+        assert!(matches!(changes.email, Some(None)));
+        assert!(changes.phone.is_none());
+        assert!(changes.birth_date.is_none());
+    }
+
+    /// 指定された項目の不正が全て報告されることを確認
+    #[test]
+    fn invalid_fields_are_reported() {
+        let req: UpdateUserRequest =
+            serde_json::from_str(r#"{"email":"not-an-email","birth_date":"2000-13-40"}"#).unwrap();
+        let fields: Vec<String> = req
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, ["email", "birth_date"]);
+    }
+}
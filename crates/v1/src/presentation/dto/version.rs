@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct VersionResponse {
+    /// `app.version` from the configuration.
+    pub version: String,
+    /// Git commit hash the binary was built from.
+    pub commit: &'static str,
+    /// Build time (RFC 3339).
+    pub built_at: Option<String>,
+}
@@ -0,0 +1,148 @@
+//! 管理者向けのハンドラ。
+
+use crate::{
+    error::AppResult,
+    infrastructure::log_level::{self, LogLevelHandle},
+    presentation::{
+        dto::{
+            admin::{LogLevelRequest, LogLevelResponse},
+            common_dto::ResponseMeta,
+            response_helper::api_ok,
+        },
+        middleware::{auth::AdminUser, validated_json::ValidatedJson},
+    },
+};
+use axum::{extract::Extension, response::IntoResponse};
+
+/// PUT /admin/log-level
+/// 再起動せずにログレベルを変更する。管理者のみ実行できる。
+pub async fn set_log_level(
+    _admin: AdminUser,
+    Extension(handle): Extension<LogLevelHandle>,
+    ValidatedJson(req): ValidatedJson<LogLevelRequest>,
+) -> AppResult<impl IntoResponse> {
+    let level = log_level::set_level(&handle, &req.level)?;
+    Ok(api_ok(
+        LogLevelResponse {
+            level: level.to_string(),
+        },
+        Some("updated"),
+        Some(ResponseMeta::current()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Captured;
+    use crate::{
+        domain::value_obj::{
+            public_id::PublicId, session_id::SessionId, user_id::UserId, user_name::UserName,
+        },
+        infrastructure::{
+            repository::user_repository::{
+                MemoryUserRepository, NewUser, ROLE_ADMIN, SharedUserRepository, UserRepository,
+            },
+            session_store::{MemorySessionStore, SharedSessionStore},
+        },
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        routing::put,
+    };
+    use chrono::Duration;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+    use tracing::debug;
+    use tracing_subscriber::{filter::LevelFilter, fmt, layer::SubscriberExt};
+
+    /// aliceを登録してセッションを作成し，`/admin/log-level`のRouterと共に返す。
+    async fn setup(handle: LogLevelHandle, admin: bool) -> (Router, SessionId) {
+        let users = Arc::new(MemoryUserRepository::new());
+        let sessions: SharedSessionStore = Arc::new(MemorySessionStore::new(Duration::hours(1)));
+        users
+            .insert(NewUser {
+                public_id: PublicId::generate(),
+                randomart: String::new(),
+                user_name: UserName::new("alice", &[]).unwrap(),
+                first_name: None,
+                last_name: None,
+                email: None,
+                phone: None,
+                birth_date: None,
+                hashed_password: "$argon2id$dummy".into(),
+            })
+            .await
+            .unwrap();
+        let alice = UserId::new(1).unwrap();
+        if admin {
+            users.set_role(alice, ROLE_ADMIN);
+        }
+        let session_id = sessions.create(alice).await.unwrap();
+
+        let users: SharedUserRepository = users;
+        let app = Router::new()
+            .route("/admin/log-level", put(set_log_level))
+            .layer(Extension(users))
+            .layer(Extension(sessions))
+            .layer(Extension(handle));
+        (app, session_id)
+    }
+
+    async fn send(app: Router, session_id: &SessionId, level: &str) -> StatusCode {
+        let request = Request::builder()
+            .method("PUT")
+            .uri("/admin/log-level")
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "level": level }).to_string(),
+            ))
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    /// レベルを変更すると，以降のdebugログが出力されることを確認
+    #[tokio::test]
+    async fn changes_level_at_runtime() {
+        let captured = Captured::default();
+        let (filter, handle) = log_level::reloadable(LevelFilter::INFO);
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_writer(captured.clone()).with_ansi(false));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        debug!("before reload");
+        let (app, session_id) = setup(handle.clone(), true).await;
+        assert_eq!(send(app, &session_id, "DEBUG").await, StatusCode::OK);
+        debug!("after reload");
+
+        let logs = captured.contents();
+        assert!(!logs.contains("before reload"), "{}", logs);
+        assert!(logs.contains("after reload"), "{}", logs);
+        assert_eq!(handle.clone_current(), Some(LevelFilter::DEBUG));
+    }
+
+    /// 不正なレベルは400になり，レベルが変わらないことを確認
+    #[tokio::test]
+    async fn unknown_level_is_bad_request() {
+        let (_filter, handle) = log_level::reloadable(LevelFilter::INFO);
+        let (app, session_id) = setup(handle.clone(), true).await;
+        assert_eq!(
+            send(app, &session_id, "verbose").await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(handle.clone_current(), Some(LevelFilter::INFO));
+    }
+
+    /// 管理者以外は403になることを確認
+    #[tokio::test]
+    async fn non_admin_is_forbidden() {
+        let (_filter, handle) = log_level::reloadable(LevelFilter::INFO);
+        let (app, session_id) = setup(handle.clone(), false).await;
+        assert_eq!(send(app, &session_id, "debug").await, StatusCode::FORBIDDEN);
+        assert_eq!(handle.clone_current(), Some(LevelFilter::INFO));
+    }
+}
@@ -0,0 +1,977 @@
+//! 認証関連（登録・ログイン）のハンドラ。
+
+use crate::{
+    config::AppConfig,
+    domain::service::{
+        password_hasher::{self, hash_password, verify_password},
+        randomart::randomart,
+    },
+    domain::value_obj::{password::Password, public_id::PublicId, user_name::UserName},
+    error::{AppError, AppResult, HashingError, with_retry_after},
+    infrastructure::{
+        login_attempt_store::SharedLoginAttemptStore,
+        repository::user_repository::{NewUser, SharedUserRepository},
+        session_store::SharedSessionStore,
+    },
+    presentation::dto::{
+        auth::{
+            AuthRequest, AuthResponse, ChangePasswordRequest, ChangePasswordResponse,
+            LogoutAllResponse, RefreshResponse, RegisterRequest, RegisterResponse,
+            ValidatedRegistration,
+        },
+        common_dto::{ApiError, ApiResponse, ResponseMeta},
+        response_helper::{api_created, api_ok},
+        user::UserResponse,
+    },
+    presentation::middleware::{auth::AuthUser, validated_json::ValidatedJson},
+};
+use axum::{
+    extract::Extension,
+    response::{IntoResponse, Response},
+};
+use chrono::Duration;
+use once_cell::sync::Lazy;
+use sha3::{Digest, Sha3_256};
+use std::sync::Arc;
+
+/// randomartの見出し。
+const RANDOMART_HEADER: &str = "USER";
+/// 新規登録の受付を切り替える`[features]`のフラグ名。
+const REGISTRATION_OPEN: &str = "registration_open";
+
+/// 存在しないユーザーでのログイン時に検証へ使用するダミーのハッシュ。
+/// ユーザーの有無で応答時間が変わらないようにするためのもの。
+static DUMMY_HASH: Lazy<String> = Lazy::new(|| {
+    hash_password("dummy-password-for-timing").expect("Failed to hash the dummy password")
+});
+
+/// POST /auth/register
+/// 入力値をVOで検証し，ユーザーとパスワードハッシュを登録する。
+/// `features.registration_open`が無効の場合は403を返す。
+#[utoipa::path(
+    post,
+    path = "/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Registered", body = ApiResponse<RegisterResponse>),
+        (status = 400, description = "Malformed JSON or unknown field", body = ApiError),
+        (status = 403, description = "Registration is closed", body = ApiError),
+        (status = 409, description = "User name, email or phone is already used", body = ApiError),
+        (status = 422, description = "Invalid fields", body = ApiError),
+        (status = 429, description = "Rate limited", body = ApiError),
+    )
+)]
+pub async fn register(
+    Extension(config): Extension<Arc<AppConfig>>,
+    Extension(users): Extension<SharedUserRepository>,
+    ValidatedJson(req): ValidatedJson<RegisterRequest>,
+) -> AppResult<impl IntoResponse> {
+    if !config.feature_enabled(REGISTRATION_OPEN) {
+        return Err(AppError::Forbidden(Some(
+            "現在，新規登録を受け付けていません。".into(),
+        )));
+    }
+    let ValidatedRegistration {
+        user_name,
+        password,
+        first_name,
+        last_name,
+        email,
+        phone,
+        birth_date,
+    } = req
+        .validate()
+        .map_err(AppError::UnprocessableContentFields)?;
+
+    let hashed_password =
+        password_hasher::hash_password_async(password.as_str().to_owned()).await?;
+    let public_id = PublicId::generate();
+    let randomart = randomart(
+        &Sha3_256::digest(public_id.as_uuid().as_bytes()),
+        RANDOMART_HEADER,
+    );
+
+    let public_id = users
+        .insert(NewUser {
+            public_id,
+            randomart: randomart.clone(),
+            user_name,
+            first_name,
+            last_name,
+            email,
+            phone,
+            birth_date,
+            hashed_password,
+        })
+        .await?;
+
+    Ok(api_created(
+        RegisterResponse {
+            public_id: public_id.to_string(),
+            randomart,
+        },
+        &format!("/users/{}", public_id),
+        Some("registered"),
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// POST /auth/login
+/// ユーザー名とパスワードを検証し，セッションを発行する。
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = AuthRequest,
+    responses(
+        (status = 200, description = "Logged in", body = ApiResponse<AuthResponse>),
+        (status = 400, description = "Malformed JSON or unknown field", body = ApiError),
+        (status = 401, description = "Wrong user name or password", body = ApiError),
+        (status = 429, description = "Rate limited or locked out", body = ApiError),
+    )
+)]
+pub async fn login(
+    Extension(users): Extension<SharedUserRepository>,
+    Extension(sessions): Extension<SharedSessionStore>,
+    lockout: Option<Extension<SharedLoginAttemptStore>>,
+    ValidatedJson(req): ValidatedJson<AuthRequest>,
+) -> AppResult<Response> {
+    // ログイン時は予約語チェックを行わず，正規化のみに使用する。
+    let user_name = UserName::new(&req.user_name, &[]).ok();
+    // ユーザー名として正しい値のみ，存在の有無に関わらず失敗回数を記録する。
+    let tracked = lockout
+        .map(|Extension(store)| store)
+        .zip(user_name.as_ref());
+    if let Some((store, name)) = &tracked
+        && let Some(remaining) = store.locked_for(name.as_str()).await?
+    {
+        return Ok(locked_out(remaining));
+    }
+    let user = match &user_name {
+        Some(user_name) => users.find_by_user_name(user_name).await?,
+        None => None,
+    };
+
+    // ユーザーが存在しない場合もダミーのハッシュで検証し，応答時間を揃える。
+    // ダミーのハッシュの初回生成もArgon2の計算となるため，ブロッキング用のスレッドで参照する。
+    let hash = user.as_ref().map(|u| u.hashed_password.clone());
+    let verified = password_hasher::spawn_blocking(move || {
+        verify_password(&req.password, hash.as_deref().unwrap_or(&DUMMY_HASH))
+    })
+    .await;
+    let user = match (user, verified) {
+        (Some(user), Ok(())) => user,
+        (_, Err(e @ (HashingError::Argon2(_) | HashingError::Task(_)))) => return Err(e.into()),
+        _ => {
+            if let Some((store, name)) = &tracked {
+                store.record_failure(name.as_str()).await?;
+            }
+            return Err(HashingError::PasswordMismatch.into());
+        }
+    };
+    if let Some((store, name)) = &tracked {
+        store.reset(name.as_str()).await?;
+    }
+
+    let session_id = sessions.create(user.user_id).await?;
+    users.record_login(user.user_id).await?;
+
+    Ok(api_ok(
+        AuthResponse {
+            public_id: user.public_id.to_string(),
+            session_id,
+            randomart: user.randomart,
+        },
+        Some("logged in"),
+        Some(ResponseMeta::current()),
+    )
+    .into_response())
+}
+
+/// ロック中のユーザー名に対するログインの429レスポンス（<Retry-After>に解除までの秒数を付与する）。
+fn locked_out(remaining: Duration) -> Response {
+    // 端数は切り上げ，解除前に再試行させないようにする。
+    let secs = (remaining.num_milliseconds().max(0) as u64).div_ceil(1000);
+    let error = AppError::TooManyRequests(Some(format!(
+        "ログインの失敗が続いたため，一時的にログインを制限しています。{}秒後に再試行してください。",
+        secs
+    )));
+    with_retry_after(error.into_response(), secs)
+}
+
+/// GET /auth/me
+/// ログイン中のユーザーのプロフィールを返す（パスワードハッシュは含まない）。
+#[utoipa::path(
+    get,
+    path = "/auth/me",
+    tag = "auth",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Current user", body = ApiResponse<UserResponse>),
+        (status = 401, description = "Not authenticated", body = ApiError),
+    )
+)]
+pub async fn me(
+    auth: AuthUser,
+    Extension(users): Extension<SharedUserRepository>,
+) -> AppResult<impl IntoResponse> {
+    let user = users
+        .find_by_user_id(auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+    Ok(api_ok(
+        UserResponse::from(user),
+        None,
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// POST /auth/logout-all
+/// ログイン中のユーザーの全てのセッション（このリクエストのものを含む）を失効させる。
+#[utoipa::path(
+    post,
+    path = "/auth/logout-all",
+    tag = "auth",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "All sessions revoked", body = ApiResponse<LogoutAllResponse>),
+        (status = 401, description = "Not authenticated", body = ApiError),
+    )
+)]
+pub async fn logout_all(
+    auth: AuthUser,
+    Extension(sessions): Extension<SharedSessionStore>,
+) -> AppResult<impl IntoResponse> {
+    let revoked = sessions.revoke_all_for_user(auth.user_id).await?;
+    Ok(api_ok(
+        LogoutAllResponse { revoked },
+        Some("logged out from all sessions"),
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// POST /auth/refresh
+/// 有効なセッションを有効期限を延長した新しいセッションに置き換え，元のセッションを失効させる。
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Session rotated", body = ApiResponse<RefreshResponse>),
+        (status = 401, description = "Not authenticated", body = ApiError),
+    )
+)]
+pub async fn refresh(
+    auth: AuthUser,
+    Extension(sessions): Extension<SharedSessionStore>,
+) -> AppResult<impl IntoResponse> {
+    let session_id = sessions.rotate(&auth.session_id).await?.ok_or_else(|| {
+        AppError::Unauthorized(Some("セッションが無効または期限切れです。".into()))
+    })?;
+    Ok(api_ok(
+        RefreshResponse { session_id },
+        Some("refreshed"),
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// POST /auth/password
+/// 現在のパスワードで再認証した上でパスワードを変更し，このリクエスト以外のセッションを失効させる。
+/// 直近`password_history.count`件のパスワードへの変更は拒否する。
+#[utoipa::path(
+    post,
+    path = "/auth/password",
+    tag = "auth",
+    security(("session" = [])),
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed", body = ApiResponse<ChangePasswordResponse>),
+        (status = 400, description = "Malformed JSON or unknown field", body = ApiError),
+        (status = 401, description = "Not authenticated or wrong current password", body = ApiError),
+        (status = 422, description = "Weak or recently used password", body = ApiError),
+    )
+)]
+pub async fn change_password(
+    auth: AuthUser,
+    Extension(users): Extension<SharedUserRepository>,
+    Extension(sessions): Extension<SharedSessionStore>,
+    Extension(config): Extension<Arc<AppConfig>>,
+    ValidatedJson(req): ValidatedJson<ChangePasswordRequest>,
+) -> AppResult<impl IntoResponse> {
+    let user = users
+        .find_by_user_id(auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+    match password_hasher::verify_password_async(req.current_password.clone(), user.hashed_password)
+        .await
+    {
+        Ok(()) => {}
+        Err(HashingError::PasswordMismatch) => {
+            return Err(AppError::Unauthorized(Some(
+                "現在のパスワードが正しくありません。".into(),
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    }
+    if req.new_password == req.current_password {
+        return Err(AppError::UnprocessableContent(Some(
+            "新しいパスワードには現在と異なるものを指定してください。".into(),
+        )));
+    }
+    let new_password = Password::new(req.new_password, Some(&user.user_name))?;
+    // ハッシュはソルトが異なるため，過去のハッシュ毎にArgon2で照合する。
+    // 照合と新しいハッシュの生成は1度のブロッキング用スレッドの処理にまとめる（再使用時は None）。
+    let history = config.password_history.count as usize;
+    let recent = users.recent_password_hashes(auth.user_id, history).await?;
+    let plain = new_password.as_str().to_owned();
+    let hashed_password = password_hasher::spawn_blocking(move || {
+        for hash in &recent {
+            match verify_password(&plain, hash) {
+                Ok(()) => return Ok(None),
+                Err(HashingError::PasswordMismatch) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        hash_password(&plain).map(Some)
+    })
+    .await?
+    .ok_or_else(|| {
+        AppError::UnprocessableContent(Some(format!(
+            "直近{}回以内に使用したパスワードは使用できません。",
+            history
+        )))
+    })?;
+    users
+        .update_password(auth.user_id, &hashed_password, history)
+        .await?;
+    let revoked_sessions = sessions
+        .revoke_all_except(auth.user_id, &auth.session_id)
+        .await?;
+
+    Ok(api_ok(
+        ChangePasswordResponse { revoked_sessions },
+        Some("password changed"),
+        Some(ResponseMeta::current()),
+    ))
+}
+
+#[cfg(test)]
+mod memory_tests {
+    use super::*;
+    use crate::{
+        domain::value_obj::{session_id::SessionId, user_id::UserId},
+        infrastructure::{
+            login_attempt_store::MemoryLoginAttemptStore,
+            repository::user_repository::MemoryUserRepository, session_store::MemorySessionStore,
+        },
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{HeaderMap, Request, StatusCode, header},
+        routing::{get, post},
+    };
+    use chrono::Duration;
+    use tower::ServiceExt;
+
+    /// `registration_open`を指定した値にしたConfig。
+    fn config(registration_open: bool) -> Arc<AppConfig> {
+        let mut config = AppConfig::new().expect("Failed to load AppConfig");
+        config
+            .features
+            .insert(REGISTRATION_OPEN.into(), registration_open);
+        Arc::new(config)
+    }
+
+    /// 全セッションの失効後，件数が返り，他のユーザーのセッションは残ることを確認
+    #[tokio::test]
+    async fn logout_all_revokes_every_session() {
+        let sessions: SharedSessionStore = Arc::new(MemorySessionStore::new(Duration::hours(1)));
+        let alice = UserId::new(1).unwrap();
+        let current = sessions.create(alice).await.unwrap();
+        sessions.create(alice).await.unwrap();
+        let bob = sessions.create(UserId::new(2).unwrap()).await.unwrap();
+
+        let app = Router::new()
+            .route("/auth/logout-all", post(logout_all))
+            .layer(Extension(sessions.clone()));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/auth/logout-all")
+            .header(header::AUTHORIZATION, format!("Bearer {}", current))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["revoked"], 2);
+
+        assert_eq!(sessions.get(&current).await.unwrap(), None);
+        assert!(sessions.get(&bob).await.unwrap().is_some());
+    }
+
+    async fn send_refresh(
+        sessions: &SharedSessionStore,
+        session_id: &SessionId,
+    ) -> (StatusCode, serde_json::Value) {
+        let app = Router::new()
+            .route("/auth/refresh", post(refresh))
+            .layer(Extension(sessions.clone()));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/auth/refresh")
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// 更新すると新しいセッションが返り，元のセッションは失効することを確認
+    #[tokio::test]
+    async fn refresh_issues_new_session() {
+        let sessions: SharedSessionStore = Arc::new(MemorySessionStore::new(Duration::hours(1)));
+        let alice = UserId::new(1).unwrap();
+        let old = sessions.create(alice).await.unwrap();
+
+        let (status, body) = send_refresh(&sessions, &old).await;
+        assert_eq!(status, StatusCode::OK);
+        let new = SessionId::parse(body["data"]["session_id"].as_str().unwrap()).unwrap();
+        assert_ne!(new, old);
+        assert_eq!(sessions.get(&new).await.unwrap(), Some(alice));
+        assert_eq!(sessions.get(&old).await.unwrap(), None);
+
+        // 失効したセッションでは更新できない。
+        let (status, _) = send_refresh(&sessions, &old).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    /// 有効期限切れのセッションは401になり，更新されないことを確認
+    #[tokio::test]
+    async fn refresh_rejects_expired_session() {
+        let sessions: SharedSessionStore = Arc::new(MemorySessionStore::new(Duration::zero()));
+        let expired = sessions.create(UserId::new(1).unwrap()).await.unwrap();
+        let (status, _) = send_refresh(&sessions, &expired).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    /// 複数の項目が不正な場合，全ての項目が<errors>として返り，登録されないことを確認
+    #[tokio::test]
+    async fn register_reports_every_invalid_field() {
+        let users: SharedUserRepository = Arc::new(MemoryUserRepository::new());
+        let app = Router::new()
+            .route("/auth/register", post(register))
+            .layer(Extension(users.clone()))
+            .layer(Extension(config(true)));
+        let body = serde_json::json!({
+            "user_name": "a",
+            "password": "Correct-Horse-42",
+            "email": "not-an-email",
+            "birth_date": "2000-13-40",
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/auth/register")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let fields: Vec<&str> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["user_name", "email", "birth_date"]);
+        let first_id = UserId::new(1).unwrap();
+        assert!(users.find_by_user_id(first_id).await.unwrap().is_none());
+    }
+
+    /// registration_openが無効の場合は403になり，登録されないことを確認
+    #[tokio::test]
+    async fn register_is_forbidden_when_closed() {
+        let users: SharedUserRepository = Arc::new(MemoryUserRepository::new());
+        let app = Router::new()
+            .route("/auth/register", post(register))
+            .layer(Extension(users.clone()))
+            .layer(Extension(config(false)));
+        let body = serde_json::json!({
+            "user_name": "alice",
+            "password": CURRENT_PASSWORD,
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/auth/register")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let first_id = UserId::new(1).unwrap();
+        assert!(users.find_by_user_id(first_id).await.unwrap().is_none());
+    }
+
+    const CURRENT_PASSWORD: &str = "Correct-Horse-42";
+
+    /// aliceを登録し，2つのセッション（今回のもの，別端末のもの）を作成する。
+    async fn setup() -> (
+        SharedUserRepository,
+        SharedSessionStore,
+        SessionId,
+        SessionId,
+    ) {
+        let users: SharedUserRepository = Arc::new(MemoryUserRepository::new());
+        let sessions: SharedSessionStore = Arc::new(MemorySessionStore::new(Duration::hours(1)));
+        users
+            .insert(NewUser {
+                public_id: PublicId::generate(),
+                randomart: String::new(),
+                user_name: UserName::new("alice", &[]).unwrap(),
+                first_name: None,
+                last_name: None,
+                email: None,
+                phone: None,
+                birth_date: None,
+                hashed_password: hash_password(CURRENT_PASSWORD).unwrap(),
+            })
+            .await
+            .unwrap();
+        let alice = UserId::new(1).unwrap();
+        let current = sessions.create(alice).await.unwrap();
+        let other = sessions.create(alice).await.unwrap();
+        (users, sessions, current, other)
+    }
+
+    async fn fetch_me(
+        users: &SharedUserRepository,
+        sessions: &SharedSessionStore,
+        authorization: Option<String>,
+    ) -> (StatusCode, serde_json::Value) {
+        let app = Router::new()
+            .route("/auth/me", get(me))
+            .layer(Extension(users.clone()))
+            .layer(Extension(sessions.clone()));
+        let mut request = Request::builder().uri("/auth/me");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// ログイン中のユーザーのプロフィールが返り，パスワードハッシュが含まれないことを確認
+    #[tokio::test]
+    async fn me_returns_current_user() {
+        let (users, sessions, current, _) = setup().await;
+        let (status, body) = fetch_me(&users, &sessions, Some(format!("Bearer {}", current))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["user_name"], "alice");
+        assert!(body["data"]["public_id"].is_string());
+        assert!(body["data"].get("hashed_password").is_none());
+        assert!(!body.to_string().contains("$argon2"));
+    }
+
+    /// 未認証・無効なセッションの場合は401になることを確認
+    #[tokio::test]
+    async fn me_requires_authentication() {
+        let (users, sessions, _, _) = setup().await;
+        let (status, _) = fetch_me(&users, &sessions, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let unknown = format!("Bearer {}", SessionId::generate());
+        let (status, _) = fetch_me(&users, &sessions, Some(unknown)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    async fn try_login(
+        users: &SharedUserRepository,
+        sessions: &SharedSessionStore,
+        lockout: &SharedLoginAttemptStore,
+        user_name: &str,
+        password: &str,
+    ) -> (StatusCode, HeaderMap, serde_json::Value) {
+        let app = Router::new()
+            .route("/auth/login", post(login))
+            .layer(Extension(users.clone()))
+            .layer(Extension(sessions.clone()))
+            .layer(Extension(lockout.clone()));
+        let body = serde_json::json!({ "user_name": user_name, "password": password });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/auth/login")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let (status, headers) = (response.status(), response.headers().clone());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, headers, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// 連続して失敗すると正しいパスワードでも429になり，存在しないユーザー名も同様にロックされることを確認
+    #[tokio::test]
+    async fn repeated_failures_lock_login() {
+        let (users, sessions, _, _) = setup().await;
+        let lockout: SharedLoginAttemptStore =
+            Arc::new(MemoryLoginAttemptStore::new(2, Duration::minutes(15)));
+
+        for user_name in ["alice", "nobody"] {
+            for _ in 0..2 {
+                let (status, _, _) =
+                    try_login(&users, &sessions, &lockout, user_name, "wrong-password").await;
+                assert_eq!(status, StatusCode::UNAUTHORIZED);
+            }
+            let (status, headers, body) =
+                try_login(&users, &sessions, &lockout, user_name, CURRENT_PASSWORD).await;
+            assert_eq!(status, StatusCode::TOO_MANY_REQUESTS, "{}", user_name);
+            assert_eq!(headers.get(header::RETRY_AFTER).unwrap(), "900");
+            assert!(body["detail"].as_str().unwrap().contains("900秒後"));
+        }
+    }
+
+    /// ログインに成功すると失敗回数がリセットされることを確認
+    #[tokio::test]
+    async fn successful_login_resets_failures() {
+        let (users, sessions, _, _) = setup().await;
+        let lockout: SharedLoginAttemptStore =
+            Arc::new(MemoryLoginAttemptStore::new(2, Duration::minutes(15)));
+
+        let (status, _, _) =
+            try_login(&users, &sessions, &lockout, "alice", "wrong-password").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _, _) =
+            try_login(&users, &sessions, &lockout, "alice", CURRENT_PASSWORD).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _, _) =
+            try_login(&users, &sessions, &lockout, "alice", "wrong-password").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _, _) =
+            try_login(&users, &sessions, &lockout, "alice", CURRENT_PASSWORD).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    async fn change(
+        users: &SharedUserRepository,
+        sessions: &SharedSessionStore,
+        session_id: &SessionId,
+        current_password: &str,
+        new_password: &str,
+    ) -> StatusCode {
+        let app = Router::new()
+            .route("/auth/password", post(change_password))
+            .layer(Extension(users.clone()))
+            .layer(Extension(sessions.clone()))
+            .layer(Extension(config(true)));
+        let body = serde_json::json!({
+            "current_password": current_password,
+            "new_password": new_password,
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/auth/password")
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    /// パスワードが更新され，他のセッションのみ失効することを確認
+    #[tokio::test]
+    async fn change_password_succeeds() {
+        let (users, sessions, current, other) = setup().await;
+        let status = change(
+            &users,
+            &sessions,
+            &current,
+            CURRENT_PASSWORD,
+            "Battery-Staple-99",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let user = users
+            .find_by_user_id(UserId::new(1).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(verify_password("Battery-Staple-99", &user.hashed_password).is_ok());
+        assert!(sessions.get(&current).await.unwrap().is_some());
+        assert_eq!(sessions.get(&other).await.unwrap(), None);
+    }
+
+    /// 現在のパスワードが誤っている場合は401になることを確認
+    #[tokio::test]
+    async fn wrong_current_password_is_unauthorized() {
+        let (users, sessions, current, other) = setup().await;
+        let status = change(
+            &users,
+            &sessions,
+            &current,
+            "Wrong-Horse-42",
+            "Battery-Staple-99",
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(sessions.get(&other).await.unwrap().is_some());
+    }
+
+    /// 現在と同じパスワードへの変更は422になることを確認
+    #[tokio::test]
+    async fn identical_password_is_rejected() {
+        let (users, sessions, current, _) = setup().await;
+        let status = change(
+            &users,
+            &sessions,
+            &current,
+            CURRENT_PASSWORD,
+            CURRENT_PASSWORD,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    /// 直近に使用したパスワードへの変更は422になり，新しいパスワードへの変更は成功することを確認
+    #[tokio::test]
+    async fn recently_used_password_is_rejected() {
+        let (users, sessions, current, _) = setup().await;
+        let changed = "Battery-Staple-99";
+        let status = change(&users, &sessions, &current, CURRENT_PASSWORD, changed).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = change(&users, &sessions, &current, changed, CURRENT_PASSWORD).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let status = change(&users, &sessions, &current, changed, "Fresh-Lantern-77").await;
+        assert_eq!(status, StatusCode::OK);
+        let user = users
+            .find_by_user_id(UserId::new(1).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(verify_password("Fresh-Lantern-77", &user.hashed_password).is_ok());
+    }
+}
+
+#[cfg(all(test, feature = "db-tests"))]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{
+        repository::user_repository::PgUserRepository, session_store::PgSessionStore,
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        routing::post,
+    };
+    use chrono::Duration;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    fn app(pool: PgPool) -> Router {
+        let sessions: SharedSessionStore =
+            Arc::new(PgSessionStore::new(pool.clone(), Duration::hours(1)));
+        let users: SharedUserRepository = Arc::new(PgUserRepository::new(pool));
+        Router::new()
+            .route("/auth/register", post(register))
+            .route("/auth/login", post(login))
+            .layer(Extension(sessions))
+            .layer(Extension(users))
+            .layer(Extension(Arc::new(
+                AppConfig::new().expect("Failed to load AppConfig"),
+            )))
+    }
+
+    fn json_request(uri: &str, body: serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    fn register_request(body: serde_json::Value) -> Request<Body> {
+        json_request("/auth/register", body)
+    }
+
+    async fn register_alice(pool: &PgPool) {
+        let body = serde_json::json!({ "user_name": "alice", "password": "Correct-Horse-42" });
+        let response = app(pool.clone())
+            .oneshot(register_request(body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    async fn login_status(pool: &PgPool, user_name: &str, password: &str) -> StatusCode {
+        let body = serde_json::json!({ "user_name": user_name, "password": password });
+        let response = app(pool.clone())
+            .oneshot(json_request("/auth/login", body))
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    /// 正しい資格情報でログインでき，セッションが作成されることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn login_succeeds(pool: PgPool) {
+        register_alice(&pool).await;
+        assert_eq!(
+            login_status(&pool, "Alice", "Correct-Horse-42").await,
+            StatusCode::OK
+        );
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    /// パスワード誤りは401になることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn login_with_wrong_password_is_unauthorized(pool: PgPool) {
+        register_alice(&pool).await;
+        assert_eq!(
+            login_status(&pool, "alice", "Wrong-Horse-42").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    /// 存在しないユーザーも同じく401になることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn login_with_unknown_user_is_unauthorized(pool: PgPool) {
+        assert_eq!(
+            login_status(&pool, "nobody", "Correct-Horse-42").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    /// 登録に成功し，ユーザーとパスワードハッシュが保存されることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn registers_user(pool: PgPool) {
+        let body = serde_json::json!({
+            "user_name": "Alice_01",
+            "password": "Correct-Horse-42",
+            "email": "alice@Example.com",
+            "phone": "090-1234-5678",
+            "birth_date": "2000-01-05",
+        });
+        let response = app(pool.clone())
+            .oneshot(register_request(body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let location = response.headers()[header::LOCATION].to_str().unwrap();
+        let public_id: uuid::Uuid = sqlx::query_scalar("SELECT public_id FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(location, format!("/users/{}", public_id));
+
+        let (user_name, email, phone): (String, String, String) =
+            sqlx::query_as("SELECT user_name, email, phone FROM users")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(user_name, "alice_01");
+        assert_eq!(email, "alice@example.com");
+        assert_eq!(phone, "+819012345678");
+
+        let hash: String = sqlx::query_scalar("SELECT current_hashed_password FROM user_auths")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(hash.starts_with("$argon2"));
+
+        let randomart: String = sqlx::query_scalar("SELECT randomart FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(randomart.starts_with("+-----[USER]"));
+    }
+
+    /// 使用済みのユーザー名は409になることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn duplicate_user_name_is_conflict(pool: PgPool) {
+        let body = serde_json::json!({ "user_name": "alice", "password": "Correct-Horse-42" });
+        let response = app(pool.clone())
+            .oneshot(register_request(body.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app(pool).oneshot(register_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    /// 大文字小文字のみが異なるメールアドレスは409になり，使用済みである旨が返ることを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn case_variant_email_is_conflict(pool: PgPool) {
+        let body = serde_json::json!({
+            "user_name": "alice",
+            "password": "Correct-Horse-42",
+            "email": "user@x.com",
+        });
+        let response = app(pool.clone())
+            .oneshot(register_request(body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = serde_json::json!({
+            "user_name": "bob",
+            "password": "Correct-Horse-42",
+            "email": "User@X.com",
+        });
+        let response = app(pool).oneshot(register_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["detail"], "このメールアドレスは既に使用されています。");
+    }
+
+    /// 検証エラーは422になり，何も登録されないことを確認
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn invalid_input_is_unprocessable(pool: PgPool) {
+        let body = serde_json::json!({ "user_name": "alice", "password": "short" });
+        let response = app(pool.clone())
+            .oneshot(register_request(body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod admin;
+pub mod auth;
+pub mod health;
+pub mod openapi;
+pub mod user;
+pub mod version;
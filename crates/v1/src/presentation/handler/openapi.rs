@@ -0,0 +1,187 @@
+//! OpenAPIの仕様とSwagger UIを返すハンドラ。
+
+use crate::{
+    config::AppConfig,
+    presentation::{
+        dto::common_dto::{ApiError, FieldError, ResponseMeta},
+        handler::{auth, user},
+    },
+};
+use axum::{Json, Router, extract::Extension, response::IntoResponse};
+use std::sync::Arc;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+use utoipa_swagger_ui::{Config, SwaggerUi};
+
+/// Swagger UIの公開を切り替える`[features]`のフラグ名。
+pub const DOCS_ENABLED: &str = "docs_enabled";
+
+/// 認証・ユーザー関連のエンドポイントのOpenAPI定義。
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "personal_rest_api_server"),
+    paths(
+        auth::register,
+        auth::login,
+        auth::me,
+        auth::logout_all,
+        auth::refresh,
+        auth::change_password,
+        user::me,
+        user::list_users,
+        user::get_user,
+        user::update_user,
+        user::delete_user,
+    ),
+    components(schemas(ApiError, FieldError, ResponseMeta)),
+    modifiers(&SessionAuth),
+    tags(
+        (name = "auth", description = "Registration, login and sessions"),
+        (name = "users", description = "User profiles"),
+    )
+)]
+pub struct ApiDoc;
+
+/// `Authorization: Bearer <session_id>`による認証を`session`として登録する。
+struct SessionAuth;
+
+impl Modify for SessionAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "session",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Session ID returned by /auth/login"))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// GET /openapi.json
+/// OpenAPIの仕様をJSONで返す。バージョンには設定された`app.version`を使用する。
+pub async fn openapi_json(Extension(config): Extension<Arc<AppConfig>>) -> impl IntoResponse {
+    let mut doc = ApiDoc::openapi();
+    doc.info.version = config.app.version.clone();
+    Json(doc)
+}
+
+/// `features.docs_enabled`が有効な場合，`/docs`に`/openapi.json`を読み込むSwagger UIのルートを返す。
+/// 無効な場合は空のRouterを返し，`/docs`は404となる。
+pub fn docs_routes<S: Clone + Send + Sync + 'static>(config: &AppConfig) -> Router<S> {
+    if !config.feature_enabled(DOCS_ENABLED) {
+        return Router::new();
+    }
+    SwaggerUi::new("/docs")
+        .config(Config::from("/openapi.json"))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    /// `docs_enabled`を指定した値にしたConfigで`/docs/`を取得する。
+    async fn fetch_docs(enabled: bool) -> axum::response::Response {
+        let mut config = AppConfig::new().expect("Failed to load AppConfig");
+        config.features.insert(DOCS_ENABLED.into(), enabled);
+        let app: Router = docs_routes(&config);
+        let request = Request::builder()
+            .uri("/docs/")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    /// 有効な場合は`/openapi.json`を読み込むHTMLを返し，無効な場合は404になることを確認
+    #[tokio::test]
+    async fn docs_follow_feature_flag() {
+        let response = fetch_docs(true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap();
+        assert!(content_type.starts_with("text/html"), "{}", content_type);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("swagger-ui"));
+
+        let response = fetch_docs(false).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn fetch_spec() -> serde_json::Value {
+        let app = Router::new()
+            .route("/openapi.json", get(openapi_json))
+            .layer(Extension(Arc::new(
+                AppConfig::new().expect("Failed to load AppConfig"),
+            )));
+        let request = Request::builder()
+            .uri("/openapi.json")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    /// 生成された仕様に`/auth/register`とRegisterRequestのスキーマが含まれることを確認
+    #[tokio::test]
+    async fn spec_contains_register() {
+        let spec = fetch_spec().await;
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        let register = &spec["paths"]["/auth/register"]["post"];
+        assert_eq!(
+            register["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/RegisterRequest"
+        );
+        for status in ["201", "409", "422"] {
+            assert!(register["responses"][status].is_object(), "{}", status);
+        }
+
+        let schema = &spec["components"]["schemas"]["RegisterRequest"];
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert_eq!(required, ["user_name", "password"]);
+        assert!(schema["properties"]["email"].is_object());
+    }
+
+    /// ユーザー関連のパスと認証方式が含まれ，レスポンスのスキーマに機密情報が無いことを確認
+    #[tokio::test]
+    async fn spec_covers_user_endpoints() {
+        let spec = fetch_spec().await;
+        let user = &spec["paths"]["/users/{public_id}"];
+        for method in ["get", "patch", "delete"] {
+            assert!(user[method].is_object(), "{}", method);
+        }
+        assert!(user["get"]["responses"]["304"].is_object());
+        assert!(spec["paths"]["/users"]["get"].is_object());
+        assert_eq!(
+            spec["components"]["securitySchemes"]["session"]["scheme"],
+            "bearer"
+        );
+
+        let properties = &spec["components"]["schemas"]["UserResponse"]["properties"];
+        assert!(properties["public_id"].is_object());
+        assert!(properties.get("hashed_password").is_none());
+        assert!(properties.get("user_id").is_none());
+    }
+}
@@ -0,0 +1,721 @@
+//! ユーザー関連のハンドラ。
+
+use crate::{
+    domain::value_obj::public_id::PublicId,
+    error::{AppError, AppResult},
+    infrastructure::{
+        repository::user_repository::SharedUserRepository, session_store::SharedSessionStore,
+    },
+    presentation::{
+        dto::{
+            auth::MeResponse,
+            common_dto::{ApiError, ApiResponse, ResponseMeta},
+            pagination::{PageParams, Paginated},
+            response_helper::{api_ok, api_ok_with_etag, api_page, version_etag},
+            user::{UpdateUserRequest, UserResponse},
+        },
+        middleware::{
+            auth::{AdminUser, AuthUser},
+            validated_json::ValidatedJson,
+            validated_query::ValidatedQuery,
+        },
+    },
+};
+use axum::{
+    extract::{Extension, Path},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+
+/// GET /me
+/// ログイン中のユーザーの公開IDを返す。
+#[utoipa::path(
+    get,
+    path = "/me",
+    tag = "users",
+    security(("session" = [])),
+    responses(
+        (status = 200, description = "Public ID of the current user", body = ApiResponse<MeResponse>),
+        (status = 401, description = "Not authenticated", body = ApiError),
+    )
+)]
+pub async fn me(
+    auth: AuthUser,
+    Extension(users): Extension<SharedUserRepository>,
+) -> AppResult<impl IntoResponse> {
+    let user = users
+        .find_by_user_id(auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+    Ok(api_ok(
+        MeResponse {
+            public_id: user.public_id.to_string(),
+        },
+        None,
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// GET /users
+/// 論理削除されていないユーザーを登録順にページ分割して返す。管理者のみ実行できる。
+#[utoipa::path(
+    get,
+    path = "/users",
+    tag = "users",
+    security(("session" = [])),
+    params(PageParams),
+    responses(
+        (status = 200, description = "One page of users", body = ApiResponse<Paginated<UserResponse>>),
+        (status = 400, description = "page or per_page is out of range", body = ApiError),
+        (status = 401, description = "Not authenticated", body = ApiError),
+        (status = 403, description = "Not an administrator", body = ApiError),
+    )
+)]
+pub async fn list_users(
+    _admin: AdminUser,
+    Extension(users): Extension<SharedUserRepository>,
+    ValidatedQuery(page): ValidatedQuery<PageParams>,
+) -> AppResult<impl IntoResponse> {
+    page.validate()?;
+
+    let (records, total) = users.list(page).await?;
+    Ok(api_page(
+        records.into_iter().map(UserResponse::from).collect(),
+        &page,
+        total,
+        None,
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// GET /users/{public_id}
+/// ユーザーを返す。本人以外のユーザーは管理者のみ参照できる。
+/// バージョンを<ETag>として返し，<If-None-Match>が一致する場合は304を返す。
+#[utoipa::path(
+    get,
+    path = "/users/{public_id}",
+    tag = "users",
+    security(("session" = [])),
+    params(
+        ("public_id" = String, Path, format = Uuid, description = "Public ID of the user"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response"),
+    ),
+    responses(
+        (status = 200, description = "The user", body = ApiResponse<UserResponse>,
+            headers(("ETag" = String, description = "Current version of the user"))),
+        (status = 304, description = "Unchanged since the given ETag"),
+        (status = 401, description = "Not authenticated", body = ApiError),
+        (status = 403, description = "Another user's profile requested by a non-administrator", body = ApiError),
+        (status = 404, description = "No such user", body = ApiError),
+    )
+)]
+pub async fn get_user(
+    auth: AuthUser,
+    Extension(users): Extension<SharedUserRepository>,
+    Path(public_id): Path<PublicId>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    // 存在の有無が分からないよう，権限の確認を先に行う。
+    let caller = users
+        .find_by_user_id(auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+    if caller.public_id != public_id && !caller.is_admin() {
+        return Err(AppError::Forbidden(Some(
+            "他のユーザーの情報は参照できません。".into(),
+        )));
+    }
+
+    let user = users
+        .find_by_public_id(&public_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(Some("ユーザーが見つかりません。".into())))?;
+    Ok(api_ok_with_etag(
+        &headers,
+        &version_etag(user.version),
+        UserResponse::from(user),
+        None,
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// PATCH /users/{public_id}
+/// 指定された項目のみプロフィールを更新する。本人のみ更新できる。
+/// <If-Match>または`version`が指定された場合は，現在のバージョンと一致する場合のみ更新する。
+#[utoipa::path(
+    patch,
+    path = "/users/{public_id}",
+    tag = "users",
+    security(("session" = [])),
+    params(
+        ("public_id" = String, Path, format = Uuid, description = "Public ID of the user"),
+        ("If-Match" = Option<String>, Header, description = "Expected version of the user"),
+    ),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "Updated user", body = ApiResponse<UserResponse>),
+        (status = 400, description = "Malformed JSON, unknown field or If-Match", body = ApiError),
+        (status = 401, description = "Not authenticated", body = ApiError),
+        (status = 403, description = "Another user's profile", body = ApiError),
+        (status = 409, description = "Stale version or duplicate email/phone", body = ApiError),
+        (status = 422, description = "Invalid fields", body = ApiError),
+        (status = 428, description = "Neither If-Match nor version was given", body = ApiError),
+    )
+)]
+pub async fn update_user(
+    auth: AuthUser,
+    Extension(users): Extension<SharedUserRepository>,
+    Path(public_id): Path<PublicId>,
+    headers: HeaderMap,
+    ValidatedJson(req): ValidatedJson<UpdateUserRequest>,
+) -> AppResult<impl IntoResponse> {
+    let caller = users
+        .find_by_user_id(auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+    if caller.public_id != public_id {
+        return Err(AppError::Forbidden(Some(
+            "他のユーザーの情報は更新できません。".into(),
+        )));
+    }
+    let changes = req
+        .validate()
+        .map_err(AppError::UnprocessableContentFields)?;
+
+    // バージョンを省略した更新を許すと同時更新を検出できないため，必ず指定させる。
+    let expected_version = if_match_version(&headers)?.or(req.version).ok_or_else(|| {
+        AppError::PreconditionRequired(Some(
+            "If-Matchまたはversionで更新対象のバージョンを指定してください。".into(),
+        ))
+    })?;
+
+    let user = users
+        .update(caller.user_id, expected_version, &changes)
+        .await?;
+    Ok(api_ok(
+        UserResponse::from(user),
+        Some("updated"),
+        Some(ResponseMeta::current()),
+    ))
+}
+
+/// <If-Match>からバージョンを読み取る（`"3"`，`W/"3"`，`3`のいずれも可）。
+fn if_match_version(headers: &HeaderMap) -> AppResult<Option<i64>> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    value
+        .to_str()
+        .ok()
+        .map(|v| v.trim().trim_start_matches("W/").trim_matches('"'))
+        .and_then(|v| v.parse::<i64>().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            AppError::BadRequest(Some("If-Matchにはバージョンを指定してください。".into()))
+        })
+}
+
+/// DELETE /users/{public_id}
+/// ユーザーを論理削除し，全てのセッションを失効させる。本人のみ削除できる。
+#[utoipa::path(
+    delete,
+    path = "/users/{public_id}",
+    tag = "users",
+    security(("session" = [])),
+    params(("public_id" = String, Path, format = Uuid, description = "Public ID of the user")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Not authenticated", body = ApiError),
+        (status = 403, description = "Another user's account", body = ApiError),
+    )
+)]
+pub async fn delete_user(
+    auth: AuthUser,
+    Extension(users): Extension<SharedUserRepository>,
+    Extension(sessions): Extension<SharedSessionStore>,
+    Path(public_id): Path<PublicId>,
+) -> AppResult<StatusCode> {
+    let caller = users
+        .find_by_user_id(auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+    if caller.public_id != public_id {
+        return Err(AppError::Forbidden(Some(
+            "他のユーザーは削除できません。".into(),
+        )));
+    }
+
+    users.soft_delete(caller.user_id).await?;
+    sessions.revoke_all_for_user(caller.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::service::password_hasher::hash_password, presentation::handler::auth::login,
+    };
+    use crate::{
+        domain::value_obj::{session_id::SessionId, user_id::UserId, user_name::UserName},
+        infrastructure::{
+            repository::user_repository::{
+                MemoryUserRepository, NewUser, ROLE_ADMIN, UserRepository,
+            },
+            session_store::{MemorySessionStore, SharedSessionStore},
+        },
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, header},
+        routing::{get, post},
+    };
+    use chrono::Duration;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    struct Fixture {
+        repo: Arc<MemoryUserRepository>,
+        sessions: SharedSessionStore,
+        alice: (PublicId, SessionId),
+        bob: (PublicId, SessionId),
+    }
+
+    async fn insert(
+        repo: &MemoryUserRepository,
+        sessions: &SharedSessionStore,
+        name: &str,
+    ) -> (PublicId, SessionId) {
+        let public_id = repo
+            .insert(NewUser {
+                public_id: PublicId::generate(),
+                randomart: String::new(),
+                user_name: UserName::new(name, &[]).unwrap(),
+                first_name: None,
+                last_name: None,
+                email: None,
+                phone: None,
+                birth_date: None,
+                hashed_password: "$argon2id$dummy".into(),
+            })
+            .await
+            .unwrap();
+        let user = repo.find_by_public_id(&public_id).await.unwrap().unwrap();
+        (public_id, sessions.create(user.user_id).await.unwrap())
+    }
+
+    async fn fixture() -> Fixture {
+        let repo = Arc::new(MemoryUserRepository::new());
+        let sessions: SharedSessionStore = Arc::new(MemorySessionStore::new(Duration::hours(1)));
+        let alice = insert(&repo, &sessions, "alice").await;
+        let bob = insert(&repo, &sessions, "bob").await;
+        Fixture {
+            repo,
+            sessions,
+            alice,
+            bob,
+        }
+    }
+
+    async fn fetch(
+        fixture: &Fixture,
+        session_id: &SessionId,
+        public_id: &PublicId,
+    ) -> (StatusCode, serde_json::Value) {
+        send(fixture, session_id, public_id, "GET", None).await
+    }
+
+    async fn patch(
+        fixture: &Fixture,
+        session_id: &SessionId,
+        public_id: &PublicId,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        send(fixture, session_id, public_id, "PATCH", Some(body)).await
+    }
+
+    async fn send(
+        fixture: &Fixture,
+        session_id: &SessionId,
+        public_id: &PublicId,
+        method: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        send_with(fixture, session_id, public_id, method, body, None).await
+    }
+
+    async fn send_with(
+        fixture: &Fixture,
+        session_id: &SessionId,
+        public_id: &PublicId,
+        method: &str,
+        body: Option<serde_json::Value>,
+        if_match: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let users: SharedUserRepository = fixture.repo.clone();
+        let app = Router::new()
+            .route(
+                "/users/{public_id}",
+                get(get_user).patch(update_user).delete(delete_user),
+            )
+            .layer(Extension(users))
+            .layer(Extension(fixture.sessions.clone()));
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("/users/{}", public_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id))
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(if_match) = if_match {
+            request = request.header(header::IF_MATCH, if_match);
+        }
+        let request = request
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    /// 本人の情報を取得でき，パスワードハッシュや内部IDが含まれないことを確認
+    #[tokio::test]
+    async fn self_access_is_ok() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let (status, body) = fetch(&f, session_id, public_id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["public_id"], public_id.to_string());
+        assert_eq!(body["data"]["user_name"], "alice");
+        assert!(body["data"].get("hashed_password").is_none());
+        assert!(body["data"].get("user_id").is_none());
+    }
+
+    /// 他のユーザーの情報は403になることを確認
+    #[tokio::test]
+    async fn cross_user_access_is_forbidden() {
+        let f = fixture().await;
+        let (status, _) = fetch(&f, &f.alice.1, &f.bob.0).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    /// 管理者は他のユーザーを参照でき，存在しないユーザーは404になることを確認
+    #[tokio::test]
+    async fn admin_access_and_not_found() {
+        let f = fixture().await;
+        f.repo.set_role(UserId::new(1).unwrap(), ROLE_ADMIN);
+        let (status, body) = fetch(&f, &f.alice.1, &f.bob.0).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["user_name"], "bob");
+
+        let (status, _) = fetch(&f, &f.alice.1, &PublicId::generate()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    async fn list(
+        fixture: &Fixture,
+        session_id: &SessionId,
+        query: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let users: SharedUserRepository = fixture.repo.clone();
+        let app = Router::new()
+            .route("/users", get(list_users))
+            .layer(Extension(users))
+            .layer(Extension(fixture.sessions.clone()));
+        let request = Request::builder()
+            .uri(format!("/users{}", query))
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// 管理者はユーザーの一覧をページ分割して取得できることを確認
+    #[tokio::test]
+    async fn admin_lists_users() {
+        let f = fixture().await;
+        insert(&f.repo, &f.sessions, "carol").await;
+        f.repo.set_role(UserId::new(1).unwrap(), ROLE_ADMIN);
+
+        let (status, body) = list(&f, &f.alice.1, "?page=2&per_page=2").await;
+        assert_eq!(status, StatusCode::OK);
+        let page = &body["data"];
+        assert_eq!(page["data"].as_array().unwrap().len(), 1);
+        assert_eq!(page["data"][0]["user_name"], "carol");
+        assert!(page["data"][0].get("hashed_password").is_none());
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["total_pages"], 2);
+
+        let (status, _) = list(&f, &f.alice.1, "?per_page=1000").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    /// 管理者以外は一覧を取得できないことを確認
+    #[tokio::test]
+    async fn non_admin_cannot_list_users() {
+        let f = fixture().await;
+        let (status, _) = list(&f, &f.alice.1, "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    async fn get_conditional(
+        fixture: &Fixture,
+        session_id: &SessionId,
+        public_id: &PublicId,
+        if_none_match: Option<&str>,
+    ) -> axum::response::Response {
+        let users: SharedUserRepository = fixture.repo.clone();
+        let app = Router::new()
+            .route("/users/{public_id}", get(get_user))
+            .layer(Extension(users))
+            .layer(Extension(fixture.sessions.clone()));
+        let mut request = Request::builder()
+            .uri(format!("/users/{}", public_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id));
+        if let Some(value) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    /// バージョンが<ETag>として返り，一致する<If-None-Match>では304，更新後は200になることを確認
+    #[tokio::test]
+    async fn conditional_get_with_etag() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+
+        let response = get_conditional(&f, session_id, public_id, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert_eq!(etag, "\"1\"");
+
+        let response = get_conditional(&f, session_id, public_id, Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(bytes.is_empty());
+
+        let body = serde_json::json!({ "first_name": "Alice", "version": 1 });
+        let (status, _) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+        let response = get_conditional(&f, session_id, public_id, Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], "\"2\"");
+    }
+
+    /// 指定した項目のみ更新され，他の項目は変更されないことを確認
+    #[tokio::test]
+    async fn partial_update() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let body = serde_json::json!({
+            "first_name": "Alice",
+            "email": "alice@example.com",
+            "version": 1,
+        });
+        let (status, body) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["first_name"], "Alice");
+
+        let body = serde_json::json!({ "last_name": "Liddell", "version": 2 });
+        let (status, body) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["first_name"], "Alice");
+        assert_eq!(body["data"]["last_name"], "Liddell");
+        assert_eq!(body["data"]["email"], "alice@example.com");
+    }
+
+    /// `null`を指定した項目が消去されることを確認
+    #[tokio::test]
+    async fn null_clears_field() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let body =
+            serde_json::json!({ "first_name": "Alice", "last_name": "Liddell", "version": 1 });
+        let (status, _) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let body = serde_json::json!({ "first_name": null, "version": 2 });
+        let (status, body) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["data"].get("first_name").is_none());
+        assert_eq!(body["data"]["last_name"], "Liddell");
+    }
+
+    /// 不正な値は422になり，何も更新されないことを確認
+    #[tokio::test]
+    async fn invalid_update_is_rejected() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let body =
+            serde_json::json!({ "first_name": "Alice", "email": "not-an-email", "version": 1 });
+        let (status, body) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["errors"][0]["field"], "email");
+
+        let (_, body) = fetch(&f, session_id, public_id).await;
+        assert!(body["data"].get("first_name").is_none());
+    }
+
+    /// 他のユーザーの情報は更新できないことを確認
+    #[tokio::test]
+    async fn cross_user_update_is_forbidden() {
+        let f = fixture().await;
+        let body = serde_json::json!({ "first_name": "Mallory", "version": 1 });
+        let (status, _) = patch(&f, &f.alice.1, &f.bob.0, body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    /// 削除後は204が返り，検索やログインができず，セッションも失効することを確認
+    #[tokio::test]
+    async fn delete_hides_user_and_blocks_login() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let alice = UserId::new(1).unwrap();
+        f.repo
+            .update_password(alice, &hash_password("Correct-Horse-42").unwrap(), 0)
+            .await
+            .unwrap();
+        let login = || async {
+            let users: SharedUserRepository = f.repo.clone();
+            let app = Router::new()
+                .route("/auth/login", post(login))
+                .layer(Extension(users))
+                .layer(Extension(f.sessions.clone()));
+            let body = serde_json::json!({ "user_name": "alice", "password": "Correct-Horse-42" });
+            let request = Request::builder()
+                .method("POST")
+                .uri("/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            app.oneshot(request).await.unwrap().status()
+        };
+        assert_eq!(login().await, StatusCode::OK);
+
+        let (status, _) = send(&f, session_id, public_id, "DELETE", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(f.repo.find_by_public_id(public_id).await.unwrap().is_none());
+        assert_eq!(f.sessions.get(session_id).await.unwrap(), None);
+        assert_eq!(login().await, StatusCode::UNAUTHORIZED);
+    }
+
+    /// 他のユーザーは削除できないことを確認
+    #[tokio::test]
+    async fn cross_user_delete_is_forbidden() {
+        let f = fixture().await;
+        let (status, _) = send(&f, &f.alice.1, &f.bob.0, "DELETE", None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(f.repo.find_by_public_id(&f.bob.0).await.unwrap().is_some());
+    }
+
+    /// 現在のバージョンを指定した更新は成功し，バージョンが進むことを確認
+    #[tokio::test]
+    async fn versioned_update_succeeds() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let body = serde_json::json!({ "first_name": "Alice" });
+        let (status, body) = send_with(
+            &f,
+            session_id,
+            public_id,
+            "PATCH",
+            Some(body),
+            Some("\"1\""),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["version"], 2);
+
+        let body = serde_json::json!({ "last_name": "Liddell", "version": 2 });
+        let (status, body) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["version"], 3);
+    }
+
+    /// 古いバージョンを指定した更新は409になることを確認
+    #[tokio::test]
+    async fn stale_version_is_conflict() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let body = serde_json::json!({ "first_name": "Alice", "version": 1 });
+        let (status, _) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let body = serde_json::json!({ "first_name": "Mallory" });
+        let (status, _) = send_with(
+            &f,
+            session_id,
+            public_id,
+            "PATCH",
+            Some(body),
+            Some("W/\"1\""),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (_, body) = fetch(&f, session_id, public_id).await;
+        assert_eq!(body["data"]["first_name"], "Alice");
+    }
+
+    /// <If-Match>もversionも無い更新は428になり，何も更新されないことを確認
+    #[tokio::test]
+    async fn missing_version_is_precondition_required() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let body = serde_json::json!({ "first_name": "Alice" });
+        let (status, body) = patch(&f, session_id, public_id, body).await;
+        assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+        assert!(body["detail"].as_str().unwrap().contains("version"));
+
+        let (_, body) = fetch(&f, session_id, public_id).await;
+        assert!(body["data"].get("first_name").is_none());
+        assert_eq!(body["data"]["version"], 1);
+    }
+
+    async fn get_me(fixture: &Fixture, session_id: &SessionId) -> (StatusCode, serde_json::Value) {
+        let users: SharedUserRepository = fixture.repo.clone();
+        let app = Router::new()
+            .route("/me", get(me))
+            .layer(Extension(users))
+            .layer(Extension(fixture.sessions.clone()));
+        let request = Request::builder()
+            .uri("/me")
+            .header(header::AUTHORIZATION, format!("Bearer {}", session_id))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// ログイン中のユーザーの公開IDが返り，論理削除後は401になることを確認
+    #[tokio::test]
+    async fn me_returns_public_id_until_deleted() {
+        let f = fixture().await;
+        let (public_id, session_id) = &f.alice;
+        let (status, body) = get_me(&f, session_id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["public_id"], public_id.to_string());
+
+        // セッションを残したまま論理削除し，ユーザーの解決に失敗することを確認する。
+        f.repo.soft_delete(UserId::new(1).unwrap()).await.unwrap();
+        let (status, _) = get_me(&f, session_id).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,69 @@
+//! バージョン情報を返すハンドラ。
+
+use crate::{
+    config::AppConfig,
+    presentation::dto::{response_helper::api_ok, version::VersionResponse},
+};
+use axum::{extract::Extension, response::IntoResponse};
+use chrono::DateTime;
+use std::sync::Arc;
+
+/// ビルド時に埋め込まれたgitのコミットハッシュ。
+const GIT_COMMIT_HASH: &str = env!("GIT_COMMIT_HASH");
+/// ビルド日時（UNIX時間）。
+const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+/// GET /version
+/// 設定されたバージョンとビルド情報を返す（機密情報は含めない）。
+pub async fn version(Extension(config): Extension<Arc<AppConfig>>) -> impl IntoResponse {
+    let built_at = BUILD_TIMESTAMP
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|dt| dt.to_rfc3339());
+    api_ok(
+        VersionResponse {
+            version: config.app.version.clone(),
+            commit: GIT_COMMIT_HASH,
+            built_at,
+        },
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    /// Extensionで共有したConfigのバージョンとビルド情報が返ることを確認
+    #[tokio::test]
+    async fn returns_configured_version() {
+        let mut config = AppConfig::new().expect("Failed to load AppConfig");
+        config.app.version = "1.2.3".into();
+
+        let app = Router::new()
+            .route("/version", get(version))
+            .layer(Extension(Arc::new(config)));
+        let request = Request::builder()
+            .uri("/version")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["version"], "1.2.3");
+        assert!(!body["data"]["commit"].as_str().unwrap().is_empty());
+        assert!(body["data"]["built_at"].is_string());
+    }
+}
@@ -0,0 +1,110 @@
+//! リクエストごとにアクセスログを出力するミドルウェア。
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::{Span, debug, info};
+
+/// ログに値を出力しないヘッダ。
+const SENSITIVE_HEADERS: [header::HeaderName; 3] =
+    [header::AUTHORIZATION, header::COOKIE, header::SET_COOKIE];
+
+/// メソッド，ルート，ステータス，処理時間（ms）をINFOで出力する。
+/// ルートはマッチしたパターン（例: `/users/{public_id}`）を使用し，IDなどの値を含めない。
+pub async fn access_log_middleware(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_owned())
+        .unwrap_or_else(|| request.uri().path().to_owned());
+    // request_id_middlewareのspanにルートを記録し，トレースからも参照できるようにする。
+    Span::current().record("route", route.as_str());
+    debug!(headers = ?redact_headers(request.headers()), "request headers");
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    info!(
+        %method,
+        route,
+        status = response.status().as_u16(),
+        latency_ms,
+        "request completed"
+    );
+    response
+}
+
+/// ヘッダを(名前, 値)の一覧に変換する。機密性の高いヘッダの値は伏せる。
+pub fn redact_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(name) {
+                "[REDACTED]".to_string()
+            } else {
+                value.to_str().unwrap_or("[non-ascii]").to_string()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Captured;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    /// ルート，ステータス，処理時間がJSONのログに出力され，IDの値は含まれないことを確認
+    #[tokio::test]
+    async fn logs_request_fields() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(captured.clone())
+            .with_max_level(tracing::Level::DEBUG)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/users/{id}", get(|| async { "ok" }))
+            .layer(middleware::from_fn(access_log_middleware));
+        let request = Request::builder()
+            .uri("/users/42")
+            .header(header::AUTHORIZATION, "Bearer secret-token")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap();
+
+        let output = captured.contents();
+        let line = output
+            .lines()
+            .find(|l| l.contains("request completed"))
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(json["level"], "INFO");
+        assert_eq!(json["fields"]["method"], "GET");
+        assert_eq!(json["fields"]["route"], "/users/{id}");
+        assert_eq!(json["fields"]["status"], 200);
+        assert!(json["fields"]["latency_ms"].is_number());
+        assert!(!output.contains("secret-token"));
+    }
+
+    /// Authorizationヘッダの値が伏せられることを確認
+    #[test]
+    fn authorization_is_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        headers.insert(header::ACCEPT, "application/json".parse().unwrap());
+        let redacted = redact_headers(&headers);
+        assert!(redacted.contains(&("authorization".into(), "[REDACTED]".into())));
+        assert!(redacted.contains(&("accept".into(), "application/json".into())));
+    }
+}
@@ -0,0 +1,224 @@
+//! `Authorization: Bearer <session_id>`からログイン中のユーザーを取り出すExtractor。
+
+use crate::{
+    domain::value_obj::{session_id::SessionId, user_id::UserId},
+    error::AppError,
+    infrastructure::{
+        repository::user_repository::{SharedUserRepository, UserRecord},
+        session_store::SharedSessionStore,
+    },
+};
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+
+/// 認証済みのユーザー。ハンドラの引数に指定すると認証必須となる。
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: UserId,
+    pub session_id: SessionId,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Unauthorized(Some("認証が必要です。".into())))?;
+        let session_id = SessionId::parse(token)?;
+
+        let sessions = parts
+            .extensions
+            .get::<SharedSessionStore>()
+            .ok_or_else(|| {
+                AppError::InternalServerError(Some("Session store is not configured".into()))
+            })?;
+        let user_id = sessions.get(&session_id).await?.ok_or_else(|| {
+            AppError::Unauthorized(Some("セッションが無効または期限切れです。".into()))
+        })?;
+
+        Ok(Self {
+            user_id,
+            session_id,
+        })
+    }
+}
+
+/// 管理者として認証済みのユーザー。ハンドラの引数に指定すると管理者のみ実行可能となる。
+/// 未認証の場合は`AuthUser`と同じく401，管理者以外は403を返す。
+#[derive(Debug, Clone)]
+pub struct AdminUser {
+    pub auth: AuthUser,
+    pub user: UserRecord,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AdminUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = AuthUser::from_request_parts(parts, state).await?;
+        let users = parts
+            .extensions
+            .get::<SharedUserRepository>()
+            .ok_or_else(|| {
+                AppError::InternalServerError(Some("User repository is not configured".into()))
+            })?;
+        let user = users
+            .find_by_user_id(auth.user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized(Some("ユーザーが存在しません。".into())))?;
+        if !user.is_admin() {
+            return Err(AppError::Forbidden(Some("管理者のみ実行できます。".into())));
+        }
+        Ok(Self { auth, user })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::value_obj::{public_id::PublicId, user_name::UserName},
+        infrastructure::{
+            repository::user_repository::{
+                MemoryUserRepository, NewUser, ROLE_ADMIN, UserRepository,
+            },
+            session_store::MemorySessionStore,
+        },
+    };
+    use axum::{
+        Router,
+        body::Body,
+        extract::Extension,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use chrono::Duration;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn app(sessions: SharedSessionStore) -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|auth: AuthUser| async move { auth.user_id.as_i64().to_string() }),
+            )
+            .layer(Extension(sessions))
+    }
+
+    fn request(authorization: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/");
+        if let Some(value) = authorization {
+            builder = builder.header(header::AUTHORIZATION, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    fn store() -> SharedSessionStore {
+        Arc::new(MemorySessionStore::new(Duration::hours(1)))
+    }
+
+    /// Authorizationヘッダが無い場合は401になることを確認
+    #[tokio::test]
+    async fn missing_header_is_unauthorized() {
+        let response = app(store()).oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// 不正・未登録のトークンは401になることを確認
+    #[tokio::test]
+    async fn invalid_token_is_unauthorized() {
+        let response = app(store())
+            .oneshot(request(Some("Bearer not-a-token")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let unknown = format!("Bearer {}", SessionId::generate());
+        let response = app(store()).oneshot(request(Some(&unknown))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// 有効なトークンでユーザーIDが取り出せることを確認
+    #[tokio::test]
+    async fn valid_token_resolves_user() {
+        let sessions = store();
+        let session_id = sessions.create(UserId::new(42).unwrap()).await.unwrap();
+        let authorization = format!("Bearer {}", session_id);
+
+        let response = app(sessions)
+            .oneshot(request(Some(&authorization)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"42");
+    }
+
+    /// aliceを登録し，`AdminUser`を要求するRouterとaliceのセッションを返す。
+    async fn admin_app(admin: bool) -> (Router, SessionId) {
+        let users = Arc::new(MemoryUserRepository::new());
+        users
+            .insert(NewUser {
+                public_id: PublicId::generate(),
+                randomart: String::new(),
+                user_name: UserName::new("alice", &[]).unwrap(),
+                first_name: None,
+                last_name: None,
+                email: None,
+                phone: None,
+                birth_date: None,
+                hashed_password: "$argon2id$dummy".into(),
+            })
+            .await
+            .unwrap();
+        let alice = UserId::new(1).unwrap();
+        if admin {
+            users.set_role(alice, ROLE_ADMIN);
+        }
+        let sessions = store();
+        let session_id = sessions.create(alice).await.unwrap();
+
+        let users: SharedUserRepository = users;
+        let app = Router::new()
+            .route(
+                "/",
+                get(|admin: AdminUser| async move { admin.user.user_name }),
+            )
+            .layer(Extension(users))
+            .layer(Extension(sessions));
+        (app, session_id)
+    }
+
+    /// 管理者は通過し，管理者以外は403，未認証は401になることを確認
+    #[tokio::test]
+    async fn admin_guard() {
+        let (app, session_id) = admin_app(true).await;
+        let authorization = format!("Bearer {}", session_id);
+        let response = app.oneshot(request(Some(&authorization))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"alice");
+
+        let (app, session_id) = admin_app(false).await;
+        let authorization = format!("Bearer {}", session_id);
+        let response = app
+            .clone()
+            .oneshot(request(Some(&authorization)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app.oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,128 @@
+//! リクエストボディのサイズ超過（413）をApiError形式のレスポンスに変換するミドルウェア。
+
+use crate::error::{AppError, PROBLEM_JSON};
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::LengthLimitError;
+use std::error::Error as StdError;
+
+/// `RequestBodyLimitLayer`やJson extractorが返す413をAppErrorに置き換える。
+/// `middleware::from_fn_with_state(max_bytes, body_limit_middleware)`として，
+/// `RequestBodyLimitLayer`の外側に配置する。
+pub async fn body_limit_middleware(
+    State(max_bytes): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    let is_problem = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v == PROBLEM_JSON);
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_problem {
+        return response;
+    }
+    AppError::PayloadTooLarge(Some(format!(
+        "リクエストボディが大きすぎます（上限: {}バイト）。",
+        max_bytes
+    )))
+    .into_response()
+}
+
+/// ボディを読み込めなかった場合のエラーを返す。
+/// `RequestBodyLimitLayer`の上限を超えた場合は413，それ以外は400とする。
+pub fn body_read_error(e: &axum::Error) -> AppError {
+    let mut source: Option<&(dyn StdError + 'static)> = Some(e);
+    while let Some(err) = source {
+        if err.is::<LengthLimitError>() {
+            return AppError::PayloadTooLarge(Some("リクエストボディが大きすぎます。".into()));
+        }
+        source = err.source();
+    }
+    AppError::BadRequest(Some("リクエストボディを読み込めませんでした。".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, body::Body, extract::DefaultBodyLimit, middleware, routing::post};
+    use tower::ServiceExt;
+    use tower_http::limit::RequestBodyLimitLayer;
+
+    const MAX_BYTES: usize = 64;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/",
+                post(|Json(body): Json<serde_json::Value>| async move { Json(body) }),
+            )
+            .layer(DefaultBodyLimit::disable())
+            .layer(RequestBodyLimitLayer::new(MAX_BYTES))
+            .layer(middleware::from_fn_with_state(
+                MAX_BYTES,
+                body_limit_middleware,
+            ))
+    }
+
+    fn json_request(body: String, with_length: bool) -> Request {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(header::CONTENT_TYPE, "application/json");
+        if with_length {
+            builder = builder.header(header::CONTENT_LENGTH, body.len());
+        }
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    fn oversized_body() -> String {
+        serde_json::json!({ "user_name": "a".repeat(MAX_BYTES) }).to_string()
+    }
+
+    async fn assert_payload_too_large(response: Response) {
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_JSON);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["status"], 413);
+        assert_eq!(
+            body["detail"],
+            "リクエストボディが大きすぎます（上限: 64バイト）。"
+        );
+    }
+
+    /// <Content-Length>が上限を超える場合，ApiError形式の413が返ることを確認
+    #[tokio::test]
+    async fn oversized_content_length_is_rejected() {
+        let response = app()
+            .oneshot(json_request(oversized_body(), true))
+            .await
+            .unwrap();
+        assert_payload_too_large(response).await;
+    }
+
+    /// <Content-Length>が無くても，読込み中に上限を超えれば413が返ることを確認
+    #[tokio::test]
+    async fn oversized_streamed_body_is_rejected() {
+        let response = app()
+            .oneshot(json_request(oversized_body(), false))
+            .await
+            .unwrap();
+        assert_payload_too_large(response).await;
+    }
+
+    /// 上限以内のボディはそのまま処理されることを確認
+    #[tokio::test]
+    async fn small_body_passes_through() {
+        let body = serde_json::json!({ "user_name": "alice" }).to_string();
+        let response = app().oneshot(json_request(body, true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,315 @@
+//! デバッグ用にリクエスト・レスポンスのボディをログに出力するミドルウェア。
+
+use crate::{
+    domain::value_obj::{email::Email, phone_number::PhoneNumber},
+    error::AppError,
+    presentation::middleware::body_limit::body_read_error,
+};
+use axum::{
+    body::{Body, Bytes, HttpBody, to_bytes},
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use tracing::{Level, debug, enabled};
+
+/// ログに出力するボディの最大バイト数（超えた分は切り詰める）。
+pub const MAX_LOGGED_BODY_BYTES: usize = 4096;
+/// バッファリングするボディの最大バイト数。
+/// 機密フィールドを伏せるにはJSON全体を解析する必要があるため，出力の上限より余裕を持たせる。
+pub const MAX_CAPTURED_BODY_BYTES: usize = 16 * MAX_LOGGED_BODY_BYTES;
+
+/// 値を伏せるJSONのフィールド名。`current_password`のように末尾が一致するものも対象とする。
+const SENSITIVE_FIELDS: [&str; 3] = ["password", "token", "session_id"];
+/// 値の一部を伏せる関数。
+type Mask = fn(&str) -> String;
+/// 値を一部伏せて出力する個人情報のフィールド名と伏せ方。末尾の一致は`SENSITIVE_FIELDS`と同様に扱う。
+const MASKED_FIELDS: [(&str, Mask); 2] = [("email", Email::mask), ("phone", PhoneNumber::mask)];
+
+/// リクエスト・レスポンスのボディをDEBUGで出力する（`logging.log_bodies`が有効な場合のみ使用する）。
+/// JSONの機密フィールドは伏せ，JSON以外のボディはサイズのみ出力する。
+/// ボディは一度バッファリングした上で再構築するため，後続のハンドラはそのまま読み込める。
+/// `MAX_CAPTURED_BODY_BYTES`を超える（または長さが不明な）ボディはバッファリングせずにそのまま渡し，
+/// 出力しなかったことのみを記録する。
+/// サイズの上限は外側のRequestBodyLimitLayerで制限されるため，その内側で使用すること。
+pub async fn body_log_middleware(request: Request, next: Next) -> Response {
+    // DEBUGが無効な場合はバッファリングのコストを避ける。
+    if !enabled!(Level::DEBUG) {
+        return next.run(request).await;
+    }
+
+    let request = if capturable(request.body()) {
+        let (parts, body) = request.into_parts();
+        let bytes = match to_bytes(body, MAX_CAPTURED_BODY_BYTES).await {
+            Ok(bytes) => bytes,
+            Err(e) => return body_read_error(&e).into_response(),
+        };
+        debug!(
+            method = %parts.method,
+            path = parts.uri.path(),
+            body = %loggable_body(&bytes),
+            "request body"
+        );
+        Request::from_parts(parts, Body::from(bytes))
+    } else {
+        debug!(
+            method = %request.method(),
+            path = request.uri().path(),
+            "request body not captured (too large or unknown length)"
+        );
+        request
+    };
+    let response = next.run(request).await;
+
+    if !capturable(response.body()) {
+        debug!(
+            status = response.status().as_u16(),
+            "response body not captured (too large or unknown length)"
+        );
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_CAPTURED_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return AppError::InternalServerError(Some(format!(
+                "Failed to buffer response body: {}",
+                e
+            )))
+            .into_response();
+        }
+    };
+    debug!(
+        status = parts.status.as_u16(),
+        body = %loggable_body(&bytes),
+        "response body"
+    );
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// 長さが`MAX_CAPTURED_BODY_BYTES`以下と分かっているボディか判定する。
+fn capturable(body: &Body) -> bool {
+    body.size_hint()
+        .upper()
+        .is_some_and(|len| len <= MAX_CAPTURED_BODY_BYTES as u64)
+}
+
+/// ボディをログ出力用の文字列に変換する。
+fn loggable_body(bytes: &Bytes) -> String {
+    if bytes.is_empty() {
+        return String::new();
+    }
+    let Ok(mut json) = serde_json::from_slice::<Value>(bytes) else {
+        return format!("<{} bytes, non-JSON>", bytes.len());
+    };
+    redact(&mut json);
+    truncate(json.to_string(), MAX_LOGGED_BODY_BYTES)
+}
+
+/// JSONを再帰的に辿り，機密フィールドの値を伏せる。
+/// 個人情報のフィールドは文字列であれば一部のみ伏せ，それ以外の型は全て伏せる。
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) {
+                    *value = Value::String("[REDACTED]".into());
+                } else if let Some(mask) = masker(key) {
+                    *value = match value {
+                        Value::String(s) => Value::String(mask(s)),
+                        _ => Value::String("[REDACTED]".into()),
+                    };
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SENSITIVE_FIELDS.iter().any(|field| key.ends_with(field))
+}
+
+fn masker(key: &str) -> Option<Mask> {
+    let key = key.to_ascii_lowercase();
+    MASKED_FIELDS
+        .iter()
+        .find(|(field, _)| key.ends_with(field))
+        .map(|(_, mask)| *mask)
+}
+
+/// 文字の境界を保ったまま`max`バイト以内に切り詰める。
+fn truncate(mut s: String, max: usize) -> String {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    let total = s.len();
+    s.truncate(end);
+    format!("{}...(truncated, {} bytes in total)", s, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Captured;
+    use axum::{Router, middleware, routing::post};
+    use tower::ServiceExt;
+
+    /// 受け取ったボディをそのまま返すRouter。
+    fn app() -> Router {
+        Router::new()
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(middleware::from_fn(body_log_middleware))
+    }
+
+    /// リクエスト・レスポンスのボディが出力され，passwordが伏せられることを確認
+    #[tokio::test]
+    async fn logs_bodies_with_redaction() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .with_max_level(Level::DEBUG)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let body = r#"{"user_name":"alice","password":"Correct-Horse-42"}"#;
+        let request = Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        // ハンドラには元のボディが渡されている。
+        let echoed = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(echoed, body.as_bytes());
+
+        let output = captured.contents();
+        assert!(output.contains("request body"), "{}", output);
+        assert!(output.contains("response body"), "{}", output);
+        assert!(output.contains("alice"), "{}", output);
+        assert!(output.contains("[REDACTED]"), "{}", output);
+        assert!(!output.contains("Correct-Horse-42"), "{}", output);
+    }
+
+    /// 入れ子や末尾一致のフィールドも伏せられることを確認
+    #[test]
+    fn redacts_nested_and_suffixed_fields() {
+        let body = Bytes::from(
+            r#"{"current_password":"a","data":{"session_id":"b","items":[{"access_token":"c"}]},"user_name":"alice"}"#,
+        );
+        let logged = loggable_body(&body);
+        for secret in [r#""a""#, r#""b""#, r#""c""#] {
+            assert!(!logged.contains(secret), "{}", logged);
+        }
+        assert!(logged.contains("alice"));
+    }
+
+    /// メールアドレスと電話番号は一部のみ伏せられ，文字列以外の値は全て伏せられることを確認
+    #[test]
+    fn masks_email_and_phone() {
+        let body = Bytes::from(
+            r#"{"email":"alice@example.com","data":{"phone":"090-1234-5678"},"backup_email":42}"#,
+        );
+        let logged = loggable_body(&body);
+        assert!(logged.contains("a***@example.com"), "{}", logged);
+        assert!(logged.contains("090-*****5678"), "{}", logged);
+        assert!(!logged.contains("alice@"), "{}", logged);
+        assert!(!logged.contains("1234"), "{}", logged);
+        assert!(!logged.contains("42"), "{}", logged);
+    }
+
+    /// 大きなボディや長さが不明なボディはバッファリングせずに渡され，出力しなかったことが記録されることを確認
+    #[tokio::test]
+    async fn large_or_streamed_bodies_are_not_captured() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .with_max_level(Level::DEBUG)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let large = "x".repeat(MAX_CAPTURED_BODY_BYTES + 1);
+        let request = Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .body(Body::from(large.clone()))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let echoed = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(echoed, large.as_bytes());
+
+        let streamed = Router::new()
+            .route(
+                "/stream",
+                post(|| async {
+                    let chunks = [Ok::<_, std::io::Error>("a"), Ok("b")];
+                    Body::from_stream(futures_util::stream::iter(chunks))
+                }),
+            )
+            .layer(middleware::from_fn(body_log_middleware));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/stream")
+            .body(Body::empty())
+            .unwrap();
+        let response = streamed.oneshot(request).await.unwrap();
+        let streamed_body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(streamed_body, "ab".as_bytes());
+
+        let output = captured.contents();
+        assert!(output.contains("request body not captured"), "{}", output);
+        assert!(output.contains("response body not captured"), "{}", output);
+        assert!(
+            !output.contains(&large[..MAX_LOGGED_BODY_BYTES]),
+            "{}",
+            output
+        );
+    }
+
+    /// 上限を超えたボディは400ではなく413になることを確認
+    #[tokio::test]
+    async fn oversized_body_is_payload_too_large() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured)
+            .with_max_level(Level::DEBUG)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = app().layer(tower_http::limit::RequestBodyLimitLayer::new(8));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/echo")
+            // Content-Lengthが無い場合は，読み込み中に上限超過が検出される。
+            .body(Body::from("0123456789"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    /// JSON以外はサイズのみ，大きなボディは切り詰めて出力されることを確認
+    #[test]
+    fn non_json_and_large_bodies() {
+        assert_eq!(
+            loggable_body(&Bytes::from("password=secret")),
+            "<15 bytes, non-JSON>"
+        );
+
+        let large = serde_json::json!({ "text": "あ".repeat(MAX_LOGGED_BODY_BYTES) }).to_string();
+        let logged = loggable_body(&Bytes::from(large));
+        assert!(logged.contains("truncated"));
+        assert!(logged.len() < MAX_LOGGED_BODY_BYTES + 64);
+    }
+}
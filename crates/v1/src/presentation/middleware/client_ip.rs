@@ -0,0 +1,190 @@
+//! 接続元のIPアドレスを特定し，ハンドラ及びtracingのspanから参照可能にするミドルウェア。
+
+use crate::error::AppError;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{HeaderMap, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tracing::Span;
+
+/// 接続元の判定に使用するHTTPヘッダ名。
+pub const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// 信頼するリバースプロキシのアドレス範囲。
+pub type TrustedProxies = Arc<Vec<IpNet>>;
+
+/// クライアントのIPアドレス。`client_ip_middleware`を経由したリクエストで取得できる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// 接続元（及び信頼するプロキシが付与した<X-Forwarded-For>）からクライアントのIPを特定し，
+/// Extensionsに格納した上で，リクエストのspanに`client_ip`として記録する。
+/// 接続元の取得には`into_make_service_with_connect_info::<SocketAddr>()`が必要。
+pub async fn client_ip_middleware(
+    State(trusted): State<TrustedProxies>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(peer) = peer {
+        let ip = resolve_client_ip(peer, request.headers(), &trusted);
+        Span::current().record("client_ip", tracing::field::display(ip));
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+    next.run(request).await
+}
+
+/// 接続元が信頼するプロキシの場合のみ<X-Forwarded-For>を参照し，
+/// 右（接続元に近い側）から順に信頼するプロキシを除いた最初のアドレスを返す。
+/// ヘッダが不正な場合は接続元のアドレスを返す。
+pub fn resolve_client_ip(peer: IpAddr, headers: &HeaderMap, trusted: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+    let Some(forwarded) = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .map(|v| v.to_str())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()
+    else {
+        return peer;
+    };
+    let Ok(hops) = forwarded
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(|hop| hop.trim().parse::<IpAddr>())
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return peer;
+    };
+
+    hops.iter()
+        .rev()
+        .find(|ip| !is_trusted(ip))
+        .or(hops.first())
+        .copied()
+        .unwrap_or(peer)
+}
+
+/// 信頼するプロキシの一覧（CIDR形式，または単一のIPアドレス）を解析する。
+pub fn parse_trusted_proxies(entries: &[String]) -> Result<Vec<IpNet>, String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry.trim();
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| format!("Invalid trusted proxy '{}'", entry))
+        })
+        .collect()
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<ClientIp>()
+            .copied()
+            .ok_or_else(|| AppError::InternalServerError(Some("Client IP is not available".into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn trusted() -> Vec<IpNet> {
+        parse_trusted_proxies(&["10.0.0.0/8".into(), "192.168.1.1".into()]).unwrap()
+    }
+
+    fn headers(xff: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_FORWARDED_FOR, xff.parse().unwrap());
+        headers
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    /// 信頼するプロキシ経由の場合，信頼しないアドレスのうち最も右のものを使用することを確認
+    #[test]
+    fn trusted_proxy_uses_forwarded_for() {
+        let headers = headers("198.51.100.7, 203.0.113.9, 10.0.0.2");
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.1"), &headers, &trusted()),
+            ip("203.0.113.9")
+        );
+        // 全て信頼するプロキシの場合は最も左のアドレス
+        let headers = self::headers("10.1.1.1, 10.0.0.2");
+        assert_eq!(
+            resolve_client_ip(ip("192.168.1.1"), &headers, &trusted()),
+            ip("10.1.1.1")
+        );
+    }
+
+    /// 信頼しない接続元からの<X-Forwarded-For>は無視されることを確認
+    #[test]
+    fn untrusted_peer_ignores_forwarded_for() {
+        let headers = headers("198.51.100.7");
+        assert_eq!(
+            resolve_client_ip(ip("203.0.113.50"), &headers, &trusted()),
+            ip("203.0.113.50")
+        );
+    }
+
+    /// 不正な<X-Forwarded-For>の場合は接続元のアドレスを使用することを確認
+    #[test]
+    fn malformed_header_falls_back_to_peer() {
+        let headers = headers("198.51.100.7, not-an-ip");
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.1"), &headers, &trusted()),
+            ip("10.0.0.1")
+        );
+        assert!(parse_trusted_proxies(&["10.0.0.0/33".into()]).is_err());
+    }
+
+    /// ミドルウェアを経由したハンドラでClientIpを取得できることを確認
+    #[tokio::test]
+    async fn extractor_returns_resolved_ip() {
+        let app = Router::new()
+            .route(
+                "/",
+                get(|ClientIp(ip): ClientIp| async move { ip.to_string() }),
+            )
+            .layer(middleware::from_fn_with_state(
+                Arc::new(trusted()),
+                client_ip_middleware,
+            ));
+        let mut request = Request::builder()
+            .uri("/")
+            .header(X_FORWARDED_FOR, "198.51.100.7")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 443))));
+        let response = app.oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"198.51.100.7");
+    }
+}
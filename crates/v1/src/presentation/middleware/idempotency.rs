@@ -0,0 +1,393 @@
+//! <Idempotency-Key>付きのPOSTに対し，最初のレスポンスを保存して再送時に返すミドルウェア。
+
+use crate::{
+    error::AppError,
+    infrastructure::idempotency_store::{SharedIdempotencyStore, StoredResponse},
+    presentation::middleware::body_limit::body_read_error,
+};
+use axum::{
+    body::{Body, HttpBody, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header::AUTHORIZATION},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha3::{Digest, Sha3_256};
+use tracing::warn;
+
+/// クライアントが付与するHTTPヘッダ名。
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+/// 保存済みのレスポンスを返したことを示すHTTPヘッダ名。
+pub const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+/// <Idempotency-Key>の最大長。
+pub const MAX_KEY_LEN: usize = 255;
+
+/// idempotency_middlewareの状態。
+#[derive(Clone)]
+pub struct IdempotencyState {
+    pub store: SharedIdempotencyStore,
+    /// 読み込むリクエストボディ・保存するレスポンスボディの最大バイト数（`app.max_body_bytes`）。
+    pub max_body_bytes: usize,
+}
+
+/// <Idempotency-Key>付きのPOSTについて，呼出し元の`(key, route)`毎に最初のレスポンスを保存する。
+/// 呼出し元は<Authorization>ヘッダのハッシュで識別し，異なる呼出し元の間でレスポンスを共有しない。
+/// 同じリクエストの再送には保存したレスポンスを返し，異なるリクエストや最初のリクエストの処理中は409を返す。
+/// 5xxのレスポンスは再試行できるよう保存しない。
+/// 上限を超えるリクエストボディは413とし，上限を超える（または長さが不明な）レスポンスは保存せずにそのまま返す。
+/// `middleware::from_fn_with_state(state, idempotency_middleware)`として使用する。
+pub async fn idempotency_middleware(
+    State(IdempotencyState {
+        store,
+        max_body_bytes,
+    }): State<IdempotencyState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let key = match request.headers().get(IDEMPOTENCY_KEY) {
+        None => return next.run(request).await,
+        Some(value) => match parse_key(value) {
+            Ok(key) => key.to_owned(),
+            Err(e) => return e.into_response(),
+        },
+    };
+    let caller = caller_id(request.headers());
+    let key = format!("{}:{}", caller, key);
+    let route = request.uri().path().to_owned();
+
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, max_body_bytes).await {
+        Ok(bytes) => bytes,
+        Err(e) => return body_read_error(&e).into_response(),
+    };
+    let request_hash = Sha3_256::new()
+        .chain_update(caller.as_bytes())
+        .chain_update([0])
+        .chain_update(&bytes)
+        .finalize()
+        .to_vec();
+
+    match store.reserve(&key, &route, &request_hash).await {
+        Ok(Some(record)) if record.request_hash != request_hash => {
+            return AppError::Conflict(Some(
+                "同じIdempotency-Keyが異なるリクエストに使用されています。".into(),
+            ))
+            .into_response();
+        }
+        Ok(Some(record)) => {
+            return match record.response {
+                Some(stored) => replay(stored),
+                None => {
+                    AppError::Conflict(Some("同じIdempotency-Keyのリクエストを処理中です。".into()))
+                        .into_response()
+                }
+            };
+        }
+        Ok(None) => {}
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    if response.status().is_server_error() {
+        release(&store, &key, &route).await;
+        return response;
+    }
+
+    // 大きなレスポンスや長さが不明なストリームはバッファリングせず，保存を諦めて再試行可能にする。
+    if response
+        .body()
+        .size_hint()
+        .upper()
+        .is_none_or(|len| len > max_body_bytes as u64)
+    {
+        release(&store, &key, &route).await;
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, max_body_bytes).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(&store, &key, &route).await;
+            return AppError::InternalServerError(Some(format!(
+                "Failed to buffer response body: {}",
+                e
+            )))
+            .into_response();
+        }
+    };
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+            .collect(),
+        body: body.to_vec(),
+    };
+    if let Err(e) = store.complete(&key, &route, stored).await {
+        warn!("Failed to store idempotent response: {}", e);
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// 呼出し元の識別子（<Authorization>ヘッダのSHA3-256の16進表現，無い場合は"anonymous"）。
+fn caller_id(headers: &HeaderMap) -> String {
+    headers
+        .get(AUTHORIZATION)
+        .map(|value| format!("{:x}", Sha3_256::digest(value.as_bytes())))
+        .unwrap_or_else(|| "anonymous".to_owned())
+}
+
+/// 処理中のレコードを解放する（失敗してもレスポンスは変えない）。
+async fn release(store: &SharedIdempotencyStore, key: &str, route: &str) {
+    if let Err(e) = store.release(key, route).await {
+        warn!("Failed to release idempotency key: {}", e);
+    }
+}
+
+/// <Idempotency-Key>の値を検証する（1〜255文字の表示可能なASCII）。
+fn parse_key(value: &HeaderValue) -> Result<&str, AppError> {
+    value
+        .to_str()
+        .ok()
+        .filter(|key| {
+            !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic())
+        })
+        .ok_or_else(|| {
+            AppError::BadRequest(Some(format!(
+                "Idempotency-Keyは1〜{}文字の表示可能なASCII文字で指定してください。",
+                MAX_KEY_LEN
+            )))
+        })
+}
+
+/// 保存したレスポンスを復元する。
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::from_bytes(&value))
+        {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::idempotency_store::MemoryIdempotencyStore;
+    use axum::{Router, middleware, routing::post};
+    use chrono::Duration;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use tower::ServiceExt;
+
+    const MAX_BODY_BYTES: usize = 64;
+
+    /// 呼ばれる度に異なるIDを201で返すハンドラを持つRouter。
+    fn app(calls: Arc<AtomicUsize>) -> Router {
+        let store: SharedIdempotencyStore =
+            Arc::new(MemoryIdempotencyStore::new(Duration::hours(1), 16));
+        Router::new()
+            .route(
+                "/users",
+                post(move |body: String| {
+                    let calls = calls.clone();
+                    async move {
+                        let n = calls.fetch_add(1, Ordering::SeqCst);
+                        (StatusCode::CREATED, format!("{}:{}", n, body))
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                IdempotencyState {
+                    store,
+                    max_body_bytes: MAX_BODY_BYTES,
+                },
+                idempotency_middleware,
+            ))
+    }
+
+    fn request(key: Option<&str>, body: &str) -> Request {
+        authorized_request(key, None, body)
+    }
+
+    fn authorized_request(key: Option<&str>, authorization: Option<&str>, body: &str) -> Request {
+        let mut builder = Request::builder().method("POST").uri("/users");
+        if let Some(key) = key {
+            builder = builder.header(IDEMPOTENCY_KEY, key);
+        }
+        if let Some(authorization) = authorization {
+            builder = builder.header(AUTHORIZATION, authorization);
+        }
+        builder.body(Body::from(body.to_owned())).unwrap()
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    /// 同じキー・同じボディの再送には保存したレスポンスが返り，ハンドラが再実行されないことを確認
+    #[tokio::test]
+    async fn retry_replays_cached_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        let first = app
+            .clone()
+            .oneshot(request(Some("k1"), "alice"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED).is_none());
+        let first_body = body_string(first).await;
+
+        let retry = app.oneshot(request(Some("k1"), "alice")).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers().get(IDEMPOTENT_REPLAYED).unwrap(), "true");
+        assert_eq!(body_string(retry).await, first_body);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// 同じキーを異なるボディで使用すると409になることを確認
+    #[tokio::test]
+    async fn conflicting_body_is_conflict() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        app.clone()
+            .oneshot(request(Some("k1"), "alice"))
+            .await
+            .unwrap();
+        let response = app.oneshot(request(Some("k1"), "bob")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// 呼出し元が異なれば同じキーでもレスポンスを共有しないことを確認
+    #[tokio::test]
+    async fn key_is_scoped_to_caller() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        let alice = app
+            .clone()
+            .oneshot(authorized_request(Some("k1"), Some("Bearer a"), "x"))
+            .await
+            .unwrap();
+        let bob = app
+            .oneshot(authorized_request(Some("k1"), Some("Bearer b"), "x"))
+            .await
+            .unwrap();
+        assert!(bob.headers().get(IDEMPOTENT_REPLAYED).is_none());
+        assert_ne!(body_string(alice).await, body_string(bob).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// 最初のリクエストの処理中に届いた再送は409となり，ハンドラが1度しか実行されないことを確認
+    #[tokio::test]
+    async fn concurrent_retry_is_conflict() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let notify = Arc::new(tokio::sync::Notify::new());
+        let store: SharedIdempotencyStore =
+            Arc::new(MemoryIdempotencyStore::new(Duration::hours(1), 16));
+        let app = Router::new()
+            .route(
+                "/users",
+                post({
+                    let (calls, notify) = (calls.clone(), notify.clone());
+                    move || async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        notify.notified().await;
+                        StatusCode::CREATED
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                IdempotencyState {
+                    store,
+                    max_body_bytes: MAX_BODY_BYTES,
+                },
+                idempotency_middleware,
+            ));
+
+        let first = tokio::spawn(app.clone().oneshot(request(Some("k1"), "alice")));
+        while calls.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        let retry = app
+            .clone()
+            .oneshot(request(Some("k1"), "alice"))
+            .await
+            .unwrap();
+        assert_eq!(retry.status(), StatusCode::CONFLICT);
+
+        notify.notify_one();
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::CREATED);
+        let replayed = app.oneshot(request(Some("k1"), "alice")).await.unwrap();
+        assert_eq!(replayed.headers().get(IDEMPOTENT_REPLAYED).unwrap(), "true");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// 上限を超えるリクエストボディは413になり，ハンドラが実行されないことを確認
+    #[tokio::test]
+    async fn oversized_request_is_payload_too_large() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        let body = "x".repeat(MAX_BODY_BYTES + 1);
+        let response = app.oneshot(request(Some("k1"), &body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    /// 上限を超えるレスポンスは保存されず，同じキーでの再送でもハンドラが実行されることを確認
+    #[tokio::test]
+    async fn oversized_response_is_not_cached() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        // ハンドラは"<n>:"を付けて返すため，レスポンスのみが上限を超える。
+        let body = "x".repeat(MAX_BODY_BYTES - 1);
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(request(Some("k1"), &body))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert!(response.headers().get(IDEMPOTENT_REPLAYED).is_none());
+            assert_eq!(body_string(response).await.len(), MAX_BODY_BYTES + 1);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// キーが無い場合は毎回ハンドラが実行され，不正なキーは400になることを確認
+    #[tokio::test]
+    async fn missing_or_invalid_key() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        app.clone().oneshot(request(None, "alice")).await.unwrap();
+        app.clone().oneshot(request(None, "alice")).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let response = app
+            .oneshot(request(Some("has space"), "alice"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
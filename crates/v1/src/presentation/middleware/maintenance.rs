@@ -0,0 +1,109 @@
+//! メンテナンス中はヘルスチェック以外のリクエストを503で拒否するミドルウェア。
+
+use crate::error::AppError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// メンテナンス中も通過させるパス。
+pub const EXEMPT_PATHS: [&str; 2] = ["/health", "/livez"];
+
+/// 実行中に切り替え可能なメンテナンスモードのフラグ。
+/// クローンしたものは同じフラグを共有する。
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceMode(Arc<AtomicBool>);
+
+impl MaintenanceMode {
+    pub fn new(enabled: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(enabled)))
+    }
+
+    /// メンテナンス中なら true を返す。
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// メンテナンスモードを切り替える。
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// メンテナンス中は`EXEMPT_PATHS`以外のリクエストに<Retry-After>付きの503を返す。
+/// `middleware::from_fn_with_state(mode, maintenance_middleware)`として使用する。
+pub async fn maintenance_middleware(
+    State(mode): State<MaintenanceMode>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if mode.is_enabled() && !EXEMPT_PATHS.contains(&request.uri().path()) {
+        return AppError::ServiceUnavailable(Some(
+            "メンテナンス中です。しばらくしてから再試行してください。".into(),
+        ))
+        .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router,
+        body::Body,
+        http::{StatusCode, header},
+        middleware,
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    fn app(mode: MaintenanceMode) -> Router {
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/livez", get(|| async { "ok" }))
+            .route("/users", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(mode, maintenance_middleware))
+    }
+
+    async fn send(app: &Router, uri: &str) -> Response {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    /// メンテナンス中は通常のルートが<Retry-After>付きの503になり，ヘルスチェックは通過することを確認
+    #[tokio::test]
+    async fn blocks_routes_except_health_checks() {
+        let app = app(MaintenanceMode::new(true));
+
+        let response = send(&app, "/users").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        for uri in EXEMPT_PATHS {
+            assert_eq!(send(&app, uri).await.status(), StatusCode::OK, "{}", uri);
+        }
+    }
+
+    /// 実行中にフラグを切り替えると直ちに反映されることを確認
+    #[tokio::test]
+    async fn toggles_at_runtime() {
+        let mode = MaintenanceMode::default();
+        let app = app(mode.clone());
+        assert_eq!(send(&app, "/users").await.status(), StatusCode::OK);
+
+        mode.set(true);
+        assert_eq!(
+            send(&app, "/users").await.status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        mode.set(false);
+        assert_eq!(send(&app, "/users").await.status(), StatusCode::OK);
+    }
+}
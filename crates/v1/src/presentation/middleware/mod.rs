@@ -0,0 +1,13 @@
+pub mod access_log;
+pub mod auth;
+pub mod body_limit;
+pub mod body_log;
+pub mod client_ip;
+pub mod idempotency;
+pub mod maintenance;
+pub mod rate_limit;
+pub mod request_id;
+pub mod timeout;
+pub mod trace_context;
+pub mod validated_json;
+pub mod validated_query;
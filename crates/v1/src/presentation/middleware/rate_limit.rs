@@ -0,0 +1,239 @@
+//! クライアントのIPアドレス毎にトークンバケットでリクエスト数を制限するミドルウェア。
+
+use crate::{error::AppError, presentation::middleware::client_ip::ClientIp};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// ロックの競合を避けるためのシャード数。
+const SHARDS: usize = 16;
+
+/// ミドルウェア間で共有するRateLimiter。
+pub type SharedRateLimiter = Arc<RateLimiter>;
+
+/// IPアドレス毎のトークンバケット。
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// IPアドレス毎のトークンバケットを保持するシャード化されたインメモリストア。
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    idle_ttl: Duration,
+    shards: Box<[Mutex<HashMap<IpAddr, Bucket>>]>,
+}
+
+impl RateLimiter {
+    /// 容量`capacity`，毎秒`refill_per_sec`個補充されるバケットを持つRateLimiterを作成する。
+    /// `idle_ttl`以上更新の無いバケットは`evict_idle`で削除される。
+    pub fn new(capacity: u32, refill_per_sec: f64, idle_ttl: Duration) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec,
+            idle_ttl,
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+        }
+    }
+
+    /// トークンを1つ消費する。不足している場合は次のトークンが補充されるまでの時間を返す。
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut shard = self.shard(&ip).lock().expect("rate limiter lock poisoned");
+        let bucket = shard.entry(ip).or_insert(Bucket {
+            tokens: self.capacity,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+
+    /// `idle_ttl`以上更新の無いバケットを削除し，削除した件数を返す。
+    pub fn evict_idle(&self) -> usize {
+        self.evict_idle_at(Instant::now())
+    }
+
+    fn evict_idle_at(&self, now: Instant) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut shard = shard.lock().expect("rate limiter lock poisoned");
+                let before = shard.len();
+                shard.retain(|_, b| now.saturating_duration_since(b.updated_at) < self.idle_ttl);
+                before - shard.len()
+            })
+            .sum()
+    }
+
+    /// 保持しているバケットの数を返す。
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().expect("rate limiter lock poisoned").len())
+            .sum()
+    }
+
+    /// バケットを1つも保持していない場合は true を返す。
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `interval`毎に`evict_idle`を実行するタスクを起動する。
+    pub fn spawn_eviction(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let limiter = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let evicted = limiter.evict_idle();
+                if evicted > 0 {
+                    debug!("Evicted {} idle rate limit buckets", evicted);
+                }
+            }
+        })
+    }
+
+    fn shard(&self, ip: &IpAddr) -> &Mutex<HashMap<IpAddr, Bucket>> {
+        let mut hasher = DefaultHasher::new();
+        ip.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+}
+
+/// `ClientIp`毎にリクエスト数を制限し，超過した場合は<Retry-After>付きの429を返す。
+/// `client_ip_middleware`より内側で`middleware::from_fn_with_state(limiter, rate_limit_middleware)`として使用する。
+/// `ClientIp`が特定できないリクエストは制限しない。
+pub async fn rate_limit_middleware(
+    State(limiter): State<SharedRateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(ClientIp(ip)) = request.extensions().get::<ClientIp>().copied() else {
+        return next.run(request).await;
+    };
+    match limiter.check(ip) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let secs = wait.as_secs_f64().ceil().max(1.0) as u64;
+            AppError::TooManyRequests(Some(secs.to_string())).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Router,
+        body::Body,
+        http::{StatusCode, header},
+        middleware,
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::from([192, 0, 2, last])
+    }
+
+    /// 容量を使い切ると拒否され，IPアドレス毎に独立していることを確認
+    #[test]
+    fn bucket_is_exhausted() {
+        let limiter = RateLimiter::new(3, 1.0, Duration::from_secs(60));
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_at(ip(1), now).is_ok());
+        }
+        assert_eq!(limiter.check_at(ip(1), now), Err(Duration::from_secs(1)));
+        assert!(limiter.check_at(ip(2), now).is_ok());
+    }
+
+    /// 経過時間に応じてトークンが補充され，容量を超えないことを確認
+    #[test]
+    fn bucket_refills_over_time() {
+        let limiter = RateLimiter::new(2, 2.0, Duration::from_secs(60));
+        let start = Instant::now();
+        assert!(limiter.check_at(ip(1), start).is_ok());
+        assert!(limiter.check_at(ip(1), start).is_ok());
+        assert!(limiter.check_at(ip(1), start).is_err());
+
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.check_at(ip(1), later).is_ok());
+        assert!(limiter.check_at(ip(1), later).is_err());
+
+        let much_later = later + Duration::from_secs(60);
+        assert!(limiter.check_at(ip(1), much_later).is_ok());
+        assert!(limiter.check_at(ip(1), much_later).is_ok());
+        assert!(limiter.check_at(ip(1), much_later).is_err());
+    }
+
+    /// 一定時間更新の無いバケットのみ削除されることを確認
+    #[test]
+    fn idle_buckets_are_evicted() {
+        let limiter = RateLimiter::new(1, 1.0, Duration::from_secs(60));
+        let start = Instant::now();
+        limiter.check_at(ip(1), start).unwrap();
+        limiter
+            .check_at(ip(2), start + Duration::from_secs(30))
+            .unwrap();
+        assert_eq!(limiter.len(), 2);
+
+        assert_eq!(limiter.evict_idle_at(start + Duration::from_secs(61)), 1);
+        assert_eq!(limiter.len(), 1);
+        assert_eq!(limiter.evict_idle_at(start + Duration::from_secs(91)), 1);
+        assert!(limiter.is_empty());
+    }
+
+    /// 制限を超えたリクエストは<Retry-After>付きの429になることを確認
+    #[tokio::test]
+    async fn over_limit_is_too_many_requests() {
+        let limiter: SharedRateLimiter =
+            Arc::new(RateLimiter::new(1, 0.1, Duration::from_secs(60)));
+        let app =
+            Router::new()
+                .route("/", get(|| async { "ok" }))
+                .layer(middleware::from_fn_with_state(
+                    limiter,
+                    rate_limit_middleware,
+                ));
+        let request = || {
+            let mut request = Request::builder().uri("/").body(Body::empty()).unwrap();
+            request.extensions_mut().insert(ClientIp(ip(1)));
+            request
+        };
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "10");
+    }
+}
@@ -0,0 +1,139 @@
+//! リクエストIDを<X-Request-Id>から取得（無ければ生成）し，リクエスト処理中に参照可能にするミドルウェア。
+
+use crate::presentation::middleware::trace_context::{continue_trace, inject_traceparent};
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use tracing::{Instrument, info_span};
+use uuid::Uuid;
+
+/// リクエストIDを受け渡すHTTPヘッダ名。
+pub const X_REQUEST_ID: &str = "x-request-id";
+
+/// 受け付けるリクエストIDの最大長。
+const MAX_LEN: usize = 128;
+
+tokio::task_local! {
+    /// 処理中のリクエストのID。
+    static REQUEST_ID: String;
+}
+
+/// リクエストのExtensionsに格納されるリクエストID。
+/// ハンドラでは`Extension<RequestId>`として受け取れる。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// 処理中のリクエストIDを返す（ミドルウェアの外側では None）。
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// <X-Request-Id>を読み取り，無効または欠落している場合はUUID v4を生成して
+/// 後続の処理をそのIDのスコープ（及びtracingのspan）内で実行する。
+/// レスポンスにも同じIDを<X-Request-Id>として付与する。
+/// <traceparent>が有効な場合はspanを分散トレースの子とし，レスポンスにはこのspanの<traceparent>を付与する。
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && v.len() <= MAX_LEN)
+        .map(str::to_owned)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+    // client_ip及びrouteは後続のミドルウェアで記録する。
+    let span = info_span!(
+        "request",
+        request_id = %id,
+        client_ip = tracing::field::Empty,
+        route = tracing::field::Empty
+    );
+    continue_trace(&span, request.headers());
+    let mut response = REQUEST_ID
+        .scope(id.clone(), next.run(request))
+        .instrument(span.clone())
+        .await;
+    inject_traceparent(&span, response.headers_mut());
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(X_REQUEST_ID, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use axum::{Router, body::Body, extract::Extension, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|| async { Err::<(), _>(AppError::NotFound(None)) }),
+            )
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    async fn instance_of(request: Request) -> String {
+        let response = app().oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        body["instance"].as_str().unwrap().to_owned()
+    }
+
+    /// <X-Request-Id>が指定された場合，そのIDがinstanceに設定されることを確認
+    #[tokio::test]
+    async fn uses_inbound_request_id() {
+        let request = Request::builder()
+            .uri("/")
+            .header(X_REQUEST_ID, "req-123")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(instance_of(request).await, "req-123");
+    }
+
+    /// 指定した<X-Request-Id>がレスポンスヘッダにそのまま返ることを確認
+    #[tokio::test]
+    async fn echoes_inbound_request_id_header() {
+        let request = Request::builder()
+            .uri("/")
+            .header(X_REQUEST_ID, "req-456")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.headers().get(X_REQUEST_ID).unwrap(), "req-456");
+    }
+
+    /// <X-Request-Id>が無い場合，生成したIDがレスポンスヘッダとExtensionsに設定されることを確認
+    #[tokio::test]
+    async fn generated_request_id_is_returned_and_extracted() {
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Extension(id): Extension<RequestId>| async move { id.0 }),
+            )
+            .layer(middleware::from_fn(request_id_middleware));
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        let header = response.headers().get(X_REQUEST_ID).unwrap().clone();
+        assert!(Uuid::parse_str(header.to_str().unwrap()).is_ok());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], header.as_bytes());
+    }
+
+    /// <X-Request-Id>が無い場合，UUID v4が生成されることを確認
+    #[tokio::test]
+    async fn generates_request_id_when_absent() {
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let instance = instance_of(request).await;
+        assert!(Uuid::parse_str(&instance).is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+//! 一定時間内に完了しないリクエストを408で打ち切るミドルウェア。
+
+use crate::error::AppError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+
+/// 後続の処理が`limit`以内に完了しない場合，`AppError::RequestTimeout`を返す。
+/// `middleware::from_fn_with_state(limit, timeout_middleware)`として使用する。
+pub async fn timeout_middleware(
+    State(limit): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => AppError::RequestTimeout(Some(format!(
+            "リクエストの処理が{}秒以内に完了しませんでした。",
+            limit.as_secs()
+        )))
+        .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn app(limit: Duration) -> Router {
+        Router::new()
+            .route("/fast", get(|| async { "ok" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "ok"
+                }),
+            )
+            .layer(middleware::from_fn_with_state(limit, timeout_middleware))
+    }
+
+    fn request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    /// 制限時間を超えた場合，408とApiErrorのBodyが返ることを確認
+    #[tokio::test]
+    async fn slow_handler_is_request_timeout() {
+        let response = app(Duration::from_millis(50))
+            .oneshot(request("/slow"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["status"], 408);
+        assert_eq!(body["message"], "Request Timeout");
+        assert!(body["detail"].is_string());
+    }
+
+    /// 制限時間内に完了した場合はそのままのレスポンスが返ることを確認
+    #[tokio::test]
+    async fn fast_handler_passes_through() {
+        let response = app(Duration::from_secs(1))
+            .oneshot(request("/fast"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,149 @@
+//! W3C Trace Context（<traceparent>）によって分散トレースを継続・伝搬するためのヘルパー。
+//! リクエストのspanを作成する`request_id_middleware`から使用する。
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::{
+    propagation::{Extractor, Injector, TextMapPropagator},
+    trace::TraceContextExt,
+};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// トレースの親を受け渡すHTTPヘッダ名。
+pub const TRACEPARENT: &str = "traceparent";
+
+/// HeaderMapからTrace Contextのヘッダを読み取るExtractor。
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+/// HeaderMapにTrace Contextのヘッダを書き込むInjector（空の値は書き込まない）。
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if value.is_empty() {
+            return;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// <traceparent>が有効な場合は`span`をそのトレースの子とし，分散トレースを継続する。
+/// 欠落・不正な場合は何もせず，`span`は新しいトレースのルートとなる。
+/// spanに入る（enterする）前に呼び出すこと。
+pub fn continue_trace(span: &Span, headers: &HeaderMap) {
+    let parent = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+    if parent.span().span_context().is_valid() {
+        // OpenTelemetryのLayerが無効な場合はエラーになるが，その場合は伝搬も不要である。
+        let _ = span.set_parent(parent);
+    }
+}
+
+/// `span`のトレースIDとspan IDを<traceparent>としてレスポンスのヘッダに付与する。
+/// OpenTelemetryのLayerが無効な場合は付与しない。
+pub fn inject_traceparent(span: &Span, headers: &mut HeaderMap) {
+    TraceContextPropagator::new().inject_context(&span.context(), &mut HeaderInjector(headers));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        infrastructure::telemetry, presentation::middleware::request_id::request_id_middleware,
+    };
+    use axum::{
+        Router, body::Body, extract::Request, middleware, response::Response, routing::get,
+    };
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const PARENT_ID: &str = "00f067aa0ba902b7";
+
+    /// OpenTelemetryのLayerを有効にしてリクエストを処理する。
+    async fn send(traceparent: Option<&str>) -> Response {
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry().with(telemetry::layer(&provider));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn(request_id_middleware));
+        let mut builder = Request::builder().uri("/");
+        if let Some(traceparent) = traceparent {
+            builder = builder.header(TRACEPARENT, traceparent);
+        }
+        app.oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    /// <traceparent>を`(trace_id, span_id)`に分解する。
+    fn parse(response: &Response) -> (String, String) {
+        let value = response
+            .headers()
+            .get(TRACEPARENT)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let parts: Vec<&str> = value.split('-').collect();
+        assert_eq!(parts.len(), 4, "{}", value);
+        assert_eq!(parts[0], "00");
+        (parts[1].to_owned(), parts[2].to_owned())
+    }
+
+    /// 有効な<traceparent>のトレースIDが継続され，レスポンスには自身のspan IDが返ることを確認
+    #[tokio::test]
+    async fn continues_valid_trace() {
+        let traceparent = format!("00-{}-{}-01", TRACE_ID, PARENT_ID);
+        let response = send(Some(&traceparent)).await;
+        let (trace_id, span_id) = parse(&response);
+        assert_eq!(trace_id, TRACE_ID);
+        assert_ne!(span_id, PARENT_ID);
+    }
+
+    /// 不正な<traceparent>は無視され，新しいトレースが開始されることを確認
+    #[tokio::test]
+    async fn malformed_traceparent_starts_new_trace() {
+        for malformed in [
+            "garbage",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        ] {
+            let response = send(Some(malformed)).await;
+            assert_eq!(response.status(), 200);
+            let (trace_id, _) = parse(&response);
+            assert_ne!(trace_id, TRACE_ID, "{}", malformed);
+            assert_ne!(trace_id, "0".repeat(32), "{}", malformed);
+        }
+    }
+
+    /// OpenTelemetryのLayerが無効な場合は<traceparent>を付与しないことを確認
+    #[tokio::test]
+    async fn no_traceparent_without_opentelemetry() {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn(request_id_middleware));
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert!(response.headers().get(TRACEPARENT).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! JSONボディをデシリアライズし，失敗時はApiErrorの400/422を返すExtractor。
+//! リクエストDTOは`#[serde(deny_unknown_fields)]`を付与し，未知のフィールドを400として拒否する。
+
+use crate::error::AppError;
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+    http::StatusCode,
+};
+use serde::de::DeserializeOwned;
+use std::error::Error;
+
+/// `axum::Json`と同様にボディを`T`にデシリアライズする。
+/// 失敗した場合はaxumのデフォルトのレスポンスではなく，
+/// 構文エラーと未知のフィールドは`AppError::BadRequest`，
+/// 型・必須項目の誤りは`AppError::UnprocessableContent`を返す。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(into_app_error)?;
+        Ok(Self(value))
+    }
+}
+
+/// JsonRejectionをAppErrorに変換する。
+fn into_app_error(rejection: JsonRejection) -> AppError {
+    // sourceには`<field path>: <reason>`形式のメッセージが入っている。
+    let reason = rejection
+        .source()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| rejection.body_text());
+    match rejection {
+        JsonRejection::JsonDataError(_) if let Some(field) = unknown_field(&reason) => {
+            AppError::BadRequest(Some(format!(
+                "未知のフィールド`{}`が含まれています（{}）。",
+                field, reason
+            )))
+        }
+        JsonRejection::JsonDataError(_) => AppError::UnprocessableContent(Some(format!(
+            "リクエストボディの値が不正です（{}）。",
+            reason
+        ))),
+        JsonRejection::JsonSyntaxError(_) => AppError::BadRequest(Some(format!(
+            "リクエストボディがJSONとして不正です（{}）。",
+            reason
+        ))),
+        JsonRejection::MissingJsonContentType(_) => AppError::BadRequest(Some(
+            "Content-Typeにapplication/jsonを指定してください。".into(),
+        )),
+        rejection if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+            AppError::PayloadTooLarge(Some("リクエストボディが大きすぎます。".into()))
+        }
+        rejection => AppError::BadRequest(Some(rejection.body_text())),
+    }
+}
+
+/// `deny_unknown_fields`によるエラーメッセージから未知のフィールド名を取り出す。
+fn unknown_field(reason: &str) -> Option<&str> {
+    let (_, rest) = reason.split_once("unknown field `")?;
+    rest.split_once('`').map(|(field, _)| field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::header, routing::post};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Input {
+        user_name: String,
+        age: u8,
+    }
+
+    fn app() -> Router {
+        Router::new().route(
+            "/",
+            post(|ValidatedJson(input): ValidatedJson<Input>| async move {
+                format!("{}:{}", input.user_name, input.age)
+            }),
+        )
+    }
+
+    async fn send(body: &str) -> (StatusCode, Vec<u8>) {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_owned()))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, bytes.to_vec())
+    }
+
+    fn detail(bytes: &[u8]) -> String {
+        let body: serde_json::Value = serde_json::from_slice(bytes).unwrap();
+        body["detail"].as_str().unwrap().to_owned()
+    }
+
+    /// 正しいJSONがデシリアライズされることを確認
+    #[tokio::test]
+    async fn valid_json_is_extracted() {
+        let (status, body) = send(r#"{"user_name":"alice","age":20}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"alice:20");
+    }
+
+    /// 構文エラーはApiErrorの400になることを確認
+    #[tokio::test]
+    async fn syntax_error_is_bad_request() {
+        let (status, body) = send(r#"{"user_name":"alice","#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(detail(&body).contains("JSON"));
+    }
+
+    /// 必須項目の欠落や型の誤りはフィールド名を含むApiErrorの422になることを確認
+    #[tokio::test]
+    async fn data_error_is_unprocessable() {
+        let (status, body) = send(r#"{"age":20}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(detail(&body).contains("user_name"), "{}", detail(&body));
+
+        let (status, body) = send(r#"{"user_name":"alice","age":"old"}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(detail(&body).contains("age"), "{}", detail(&body));
+    }
+
+    /// 未知のフィールドはフィールド名を含むApiErrorの400になることを確認
+    #[tokio::test]
+    async fn unknown_field_is_bad_request() {
+        let (status, body) = send(r#"{"username":"alice","age":20}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(detail(&body).contains("`username`"), "{}", detail(&body));
+    }
+}
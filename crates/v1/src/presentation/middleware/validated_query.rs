@@ -0,0 +1,97 @@
+//! クエリ文字列をデシリアライズし，失敗時はApiErrorの422を返すExtractor。
+
+use crate::error::AppError;
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+use std::error::Error;
+
+/// `axum::extract::Query`と同様にクエリ文字列を`T`にデシリアライズする。
+/// 失敗した場合はプレーンテキストの400ではなく，
+/// 不正なフィールドを含む`AppError::UnprocessableContent`を返す。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::try_from_uri(&parts.uri).map_err(|rejection| {
+            // sourceには`<field>: <reason>`形式のメッセージが入っている。
+            let reason = rejection
+                .source()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| rejection.body_text());
+            AppError::UnprocessableContent(Some(format!(
+                "クエリパラメータが不正です（{}）。",
+                reason
+            )))
+        })?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::dto::pagination::{DEFAULT_PER_PAGE, PageParams};
+    use axum::{
+        Json, Router,
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new().route(
+            "/items",
+            get(
+                |ValidatedQuery(params): ValidatedQuery<PageParams>| async move {
+                    Json(serde_json::json!({ "page": params.page, "per_page": params.per_page }))
+                },
+            ),
+        )
+    }
+
+    async fn get_json(uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// 正しいクエリがデシリアライズされ，省略した値はデフォルトになることを確認
+    #[tokio::test]
+    async fn valid_query_is_extracted() {
+        let (status, body) = get_json("/items?page=3").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["page"], 3);
+        assert_eq!(body["per_page"], DEFAULT_PER_PAGE);
+    }
+
+    /// 型の合わないクエリはフィールド名を含むApiErrorの422になることを確認
+    #[tokio::test]
+    async fn type_mismatch_is_unprocessable() {
+        let (status, body) = get_json("/items?page=1&per_page=many").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["status"], 422);
+        assert_eq!(body["message"], "Unprocessable Entity");
+        assert!(
+            body["detail"].as_str().unwrap().contains("per_page"),
+            "{}",
+            body["detail"]
+        );
+    }
+}
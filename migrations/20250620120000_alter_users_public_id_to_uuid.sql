@@ -0,0 +1,11 @@
+-- public_idをUUID v7で発行するため，型をUUIDに変更する。
+-- 既存のnanoidはUUIDに変換できないため，新しい列を追加して採番し直してから入れ替える。
+-- 既存行にはUUID v4を割り当て，以降の行はアプリケーションがUUID v7を発行する。
+ALTER TABLE users ADD COLUMN public_id_uuid UUID;
+UPDATE users SET public_id_uuid = gen_random_uuid();
+ALTER TABLE users ALTER COLUMN public_id_uuid SET NOT NULL;
+
+ALTER TABLE users DROP CONSTRAINT users_public_id_key;
+ALTER TABLE users DROP COLUMN public_id;
+ALTER TABLE users RENAME COLUMN public_id_uuid TO public_id;
+ALTER TABLE users ADD CONSTRAINT users_public_id_key UNIQUE (public_id);
@@ -0,0 +1,2 @@
+-- session_idを256bitのbase64urlトークンで発行するため，型を文字列に変更する。
+ALTER TABLE sessions ALTER COLUMN session_id TYPE VARCHAR(43) USING session_id::text;
@@ -0,0 +1,2 @@
+-- ユーザーを論理削除するため，削除日時の列を追加する。
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMPTZ;
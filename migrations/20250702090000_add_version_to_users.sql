@@ -0,0 +1,2 @@
+-- 楽観的排他制御のため，更新の度に加算するバージョンの列を追加する。
+ALTER TABLE users ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
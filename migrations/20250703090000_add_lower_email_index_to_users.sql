@@ -0,0 +1,25 @@
+-- メールアドレスは大文字小文字を区別せず一意とするため，小文字化した上で式インデックスを追加する。
+-- 大文字小文字のみが異なる重複があると小文字化で一意制約に違反するため，先に検出して中止する。
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(format('%s (user_id: %s)', lower_email, user_ids), ', ')
+    INTO duplicates
+    FROM (
+        SELECT lower(email) AS lower_email, string_agg(user_id::TEXT, ', ' ORDER BY user_id) AS user_ids
+        FROM users
+        WHERE email IS NOT NULL
+        GROUP BY lower(email)
+        HAVING count(*) > 1
+    ) AS d;
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION '大文字小文字のみが異なるメールアドレスが重複しています。手動で解消してから再実行してください: %', duplicates;
+    END IF;
+END
+$$;
+
+UPDATE users SET email = lower(email) WHERE email <> lower(email);
+CREATE UNIQUE INDEX users_email_lower_key ON users (lower(email));
+-- 一意性は式インデックスのみで保証する。
+ALTER TABLE users DROP CONSTRAINT users_email_key;
@@ -0,0 +1,22 @@
+-- パスワードの再利用を防ぐため，過去のパスワードハッシュを保持するテーブルを作成する。
+-- 保持する件数はアプリケーション側の設定（password_history.count）で決まる。
+CREATE TABLE IF NOT EXISTS password_histories (
+    history_id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    hashed_password VARCHAR(128) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+CREATE INDEX password_histories_user_id_idx ON password_histories (user_id, history_id DESC);
+
+-- 固定の列に保持していた直前2件を古い順に移行する。
+INSERT INTO password_histories (user_id, hashed_password, created_at)
+SELECT user_id, prev_hashed_password_2, updated_at FROM user_auths
+WHERE prev_hashed_password_2 IS NOT NULL;
+INSERT INTO password_histories (user_id, hashed_password, created_at)
+SELECT user_id, prev_hashed_password_1, updated_at FROM user_auths
+WHERE prev_hashed_password_1 IS NOT NULL;
+
+ALTER TABLE user_auths
+    DROP COLUMN prev_hashed_password_1,
+    DROP COLUMN prev_hashed_password_2;